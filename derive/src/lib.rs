@@ -0,0 +1,429 @@
+/*!
+Procedural macros for the Alphabill predicate SDK.
+
+The macros are re-exported by the `alphabill` crate and should be used through
+it, see the SDK documentation for details.
+*/
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    Data, DeriveInput, Fields, GenericArgument, ItemFn, LitInt, LitStr, PathArguments, ReturnType,
+    Token, Type, parenthesized, parse_macro_input,
+};
+
+/**
+Implements `from(input: impl AsRef<[u8]>) -> Result<Self, Error>` method which
+decodes the struct from the tag-value encoded data (as returned by the host).
+
+Fields to decode are marked with `#[ab(tag = N)]` attribute, fields without the
+attribute are left to their default value.
+
+When the field fails to decode the error is chained with the tag of the field,
+for `Option<T>` fields the value is decoded as `T` so ie invalid value of the
+`Option<u64>` field with tag 4 results in error code `0x0104` (the hand written
+decoders used to chain extra `1` for `Option<u64>` fields, ie `0x010104`).
+*/
+#[proc_macro_derive(AbDecode, attributes(ab))]
+pub fn derive_ab_decode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    ab_decode(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn ab_decode(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(s) => match &s.fields {
+            Fields::Named(f) => &f.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "AbDecode can only be derived for structs with named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "AbDecode can only be derived for structs",
+            ));
+        }
+    };
+
+    let mut tags = Vec::new();
+    let mut arms = Vec::new();
+    for fld in fields {
+        let Some(tag) = field_tag(fld)? else {
+            continue;
+        };
+        if tags.contains(&tag) {
+            return Err(syn::Error::new_spanned(
+                fld,
+                format!("tag {tag} is already assigned to another field"),
+            ));
+        }
+        tags.push(tag);
+
+        let name = &fld.ident;
+        let cfg = fld.attrs.iter().filter(|a| a.path().is_ident("cfg"));
+        let value = match option_inner(&fld.ty) {
+            Some(ty) => quote! { ::core::option::Option::Some(
+                <#ty as ::core::convert::TryFrom<::alphabill::decoder::Value>>::try_from(v)
+                    .map_err(|err| err.chain(#tag))?
+            ) },
+            None => {
+                let ty = &fld.ty;
+                quote! {
+                    <#ty as ::core::convert::TryFrom<::alphabill::decoder::Value>>::try_from(v)
+                        .map_err(|err| err.chain(#tag))?
+                }
+            }
+        };
+        arms.push(quote! {
+            #(#cfg)*
+            (#tag, v) => r.#name = #value,
+        });
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// decodes the struct from the tag-value encoded data, unknown tags are ignored.
+            pub fn from(input: impl ::core::convert::AsRef<[u8]>) -> ::core::result::Result<Self, ::alphabill::error::Error> {
+                let mut r = <Self as ::core::default::Default>::default();
+                for fld in ::alphabill::decoder::TryTagValueIter::new(input.as_ref()) {
+                    match fld? {
+                        #(#arms)*
+                        _ => (), // unknown field to us, ignore
+                    }
+                }
+                ::core::result::Result::Ok(r)
+            }
+        }
+    })
+}
+
+/**
+Turns the function into exported predicate function.
+
+The function must not have parameters and it's return type must implement the
+`PredicateResult` trait, the value is converted to the `u64` when returned to the host.
+
+Error codes the predicate may return can be listed as `error(code, "description")`
+arguments, they are added to the documentation of the function.
+
+Visibility of the function is preserved, the symbol is exported by `#[unsafe(no_mangle)]`.
+*/
+#[proc_macro_attribute]
+pub fn predicate(args: TokenStream, input: TokenStream) -> TokenStream {
+    let mut errors = Vec::new();
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("error") {
+            let content;
+            parenthesized!(content in meta.input);
+            let code: LitInt = content.parse()?;
+            content.parse::<Token![,]>()?;
+            let desc: LitStr = content.parse()?;
+            errors.push((code, desc));
+            Ok(())
+        } else {
+            Err(meta
+                .error("unsupported predicate attribute, expected `error(code, \"description\")`"))
+        }
+    });
+    parse_macro_input!(args with parser);
+    let input = parse_macro_input!(input as ItemFn);
+    predicate_fn(input, &errors)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn predicate_fn(input: ItemFn, errors: &[(LitInt, LitStr)]) -> syn::Result<TokenStream2> {
+    let sig = &input.sig;
+    if !sig.inputs.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &sig.inputs,
+            "predicate function must not have parameters",
+        ));
+    }
+    if !sig.generics.params.is_empty() || sig.asyncness.is_some() || sig.unsafety.is_some() {
+        return Err(syn::Error::new_spanned(
+            sig,
+            "predicate function must not be generic, async or unsafe",
+        ));
+    }
+    let ReturnType::Type(_, ret) = &sig.output else {
+        return Err(syn::Error::new_spanned(
+            sig,
+            "predicate function must return a value implementing PredicateResult",
+        ));
+    };
+
+    let mut docs = Vec::new();
+    if !errors.is_empty() {
+        docs.push(String::new());
+        docs.push(" ## Errors".to_string());
+        for (code, desc) in errors {
+            docs.push(format!(" - `{code}`: {};", desc.value()));
+        }
+    }
+
+    let name = &sig.ident;
+    let vis = &input.vis;
+    let attrs = &input.attrs;
+    let body = &input.block;
+    Ok(quote! {
+        #(#attrs)*
+        #(#[doc = #docs])*
+        #[unsafe(no_mangle)]
+        #vis extern "C" fn #name() -> u64 {
+            fn predicate() -> #ret #body
+            ::alphabill::PredicateResult::into_u64(predicate())
+        }
+    })
+}
+
+/**
+Implements `FromItem` and `PredicateConfig` traits for the struct, fields are
+decoded from the CBOR array items in the order they are declared.
+
+Supported struct attributes (`#[config(...)]`):
+ - `len = N`: the array must have exactly `N` items.
+
+Supported field attributes (`#[config(...)]`):
+ - `index = N`: position of the item (starting from 0) in the array, required
+   for fields with `#[cfg]` attribute and all the fields following them as the
+   disabled fields are not visible to the macro;
+ - `error = N`: code to chain to the error when decoding the field fails,
+   by default the position of the item (starting from 1) is used;
+ - `handle`: the field is `Handle<Bytes>`, `Handle<Proof>` or `Handle<PubKeyHash>`
+   (or `Option` of it) of the host variable created from the item;
+ - `default`: use the default value when the item is missing;
+ - `rest`: the field is `Vec` collecting all the remaining items, must be the last field.
+*/
+#[proc_macro_derive(PredicateConfig, attributes(config))]
+pub fn derive_predicate_config(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    predicate_config(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[derive(Default)]
+struct ConfigAttr {
+    index: Option<usize>,
+    error: Option<u8>,
+    handle: bool,
+    default: bool,
+    rest: bool,
+}
+
+fn predicate_config(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(s) => match &s.fields {
+            Fields::Named(f) => &f.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "PredicateConfig can only be derived for structs with named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "PredicateConfig can only be derived for structs",
+            ));
+        }
+    };
+
+    let len = struct_len(&input)?;
+    let mut inits = Vec::new();
+    let mut after_cfg = false;
+    for (pos, fld) in fields.iter().enumerate() {
+        let attr = config_attr(fld)?;
+        if attr.rest && pos + 1 != fields.len() {
+            return Err(syn::Error::new_spanned(
+                fld,
+                "only the last field can collect the rest of the items",
+            ));
+        }
+        after_cfg |= fld.attrs.iter().any(|a| a.path().is_ident("cfg"));
+        let idx = match attr.index {
+            Some(idx) => idx,
+            None if after_cfg => {
+                return Err(syn::Error::new_spanned(
+                    fld,
+                    "fields with `#[cfg]` and the fields following them must set the item position with `#[config(index = N)]`",
+                ));
+            }
+            None => pos,
+        };
+        let code = match attr.error {
+            Some(c) => c,
+            None => u8::try_from(idx + 1).map_err(|_| {
+                syn::Error::new_spanned(fld, "error code must be assigned explicitly")
+            })?,
+        };
+
+        let ty = &fld.ty;
+        let convert = |ty: &Type| {
+            if attr.handle {
+                quote! { ::core::result::Result::Ok(::core::convert::From::from(::alphabill::cbor::add_var(v))) }
+            } else {
+                quote! { <#ty as ::alphabill::cbor::FromItem>::from_item(v) }
+            }
+        };
+        let value = if attr.rest {
+            let Some(inner) = type_arg(ty, "Vec") else {
+                return Err(syn::Error::new_spanned(
+                    ty,
+                    "rest of the items must be collected into Vec",
+                ));
+            };
+            let conv = convert(inner);
+            quote! {
+                items.iter().skip(#idx).map(|v| #conv).collect::<::core::result::Result<_, _>>()
+            }
+        } else {
+            let conv = match (attr.handle, type_arg(ty, "Option")) {
+                (true, Some(_)) => quote! {
+                    ::core::result::Result::Ok((!v.is_null()).then(|| ::core::convert::From::from(::alphabill::cbor::add_var(v))))
+                },
+                _ => convert(ty),
+            };
+            let missing = if attr.default {
+                quote! { ::core::result::Result::Ok(::core::default::Default::default()) }
+            } else if type_arg(ty, "Option").is_some() {
+                quote! { ::core::result::Result::Ok(::core::option::Option::None) }
+            } else {
+                quote! { ::core::result::Result::Err(::alphabill::error::Error::new(::alphabill::cbor::ERR_MISSING_ITEM)) }
+            };
+            quote! {
+                match items.get(#idx) {
+                    ::core::option::Option::Some(v) => #conv,
+                    ::core::option::Option::None => #missing,
+                }
+            }
+        };
+
+        let name = &fld.ident;
+        inits.push(quote! {
+            #name: (#value).map_err(|err: ::alphabill::error::Error| err.chain(#code))?,
+        });
+    }
+
+    let check_len = len.map(|len| {
+        quote! {
+            if items.len() != #len {
+                return ::core::result::Result::Err(::alphabill::error::Error::new(::alphabill::cbor::ERR_INVALID_TYPE));
+            }
+        }
+    });
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::alphabill::cbor::FromItem for #name #ty_generics #where_clause {
+            fn from_item(item: &::alphabill::cbor::Item) -> ::core::result::Result<Self, ::alphabill::error::Error> {
+                let ::core::option::Option::Some(items) = item.as_array() else {
+                    return ::core::result::Result::Err(::alphabill::error::Error::new(::alphabill::cbor::ERR_INVALID_TYPE));
+                };
+                #check_len
+                ::core::result::Result::Ok(Self {
+                    #(#inits)*
+                })
+            }
+        }
+
+        impl #impl_generics ::alphabill::evaluation_ctx::PredicateConfig for #name #ty_generics #where_clause {}
+    })
+}
+
+/// returns the options set by the `#[config(...)]` attribute of the field.
+fn config_attr(fld: &syn::Field) -> syn::Result<ConfigAttr> {
+    let mut r = ConfigAttr::default();
+    for attr in fld.attrs.iter().filter(|a| a.path().is_ident("config")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("index") {
+                let lit: LitInt = meta.value()?.parse()?;
+                r.index = Some(lit.base10_parse::<usize>()?);
+            } else if meta.path.is_ident("error") {
+                let lit: LitInt = meta.value()?.parse()?;
+                r.error = Some(lit.base10_parse::<u8>()?);
+            } else if meta.path.is_ident("handle") {
+                r.handle = true;
+            } else if meta.path.is_ident("default") {
+                r.default = true;
+            } else if meta.path.is_ident("rest") {
+                r.rest = true;
+            } else {
+                return Err(meta.error(
+                    "unsupported config attribute, expected one of `index = N`, `error = N`, `handle`, `default`, `rest`",
+                ));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(r)
+}
+
+/// returns the value of the `#[config(len = N)]` attribute of the struct.
+fn struct_len(input: &DeriveInput) -> syn::Result<Option<usize>> {
+    let mut len = None;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("config")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("len") {
+                let lit: LitInt = meta.value()?.parse()?;
+                len = Some(lit.base10_parse::<usize>()?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported config attribute, expected `len = N`"))
+            }
+        })?;
+    }
+    Ok(len)
+}
+
+/// returns the value of the `#[ab(tag = N)]` attribute of the field.
+fn field_tag(fld: &syn::Field) -> syn::Result<Option<u8>> {
+    let mut tag = None;
+    for attr in fld.attrs.iter().filter(|a| a.path().is_ident("ab")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("tag") {
+                let lit: LitInt = meta.value()?.parse()?;
+                tag = Some(lit.base10_parse::<u8>()?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported ab attribute, expected `tag = N`"))
+            }
+        })?;
+    }
+    Ok(tag)
+}
+
+/// if the type is `Option<T>` returns the `T`.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    type_arg(ty, "Option")
+}
+
+/// if the type is `name<T>` returns the `T`.
+fn type_arg<'a>(ty: &'a Type, name: &str) -> Option<&'a Type> {
+    let Type::Path(tp) = ty else {
+        return None;
+    };
+    let seg = tp.path.segments.last()?;
+    if seg.ident != name {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &seg.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(t) if args.args.len() == 1 => Some(t),
+        _ => None,
+    }
+}
@@ -41,12 +41,10 @@ use alphabill::txsystem::token::nft;
     feature = "token-update-data",
     feature = "token-bearer",
 ))]
-use alphabill::{
-    cbor,
-    decoder::{self, Value},
-    error::Error,
-    evaluation_ctx::ABHandle,
-};
+use alphabill::PredicateConfig;
+
+#[cfg(any(feature = "token-update-data", feature = "token-bearer"))]
+use alphabill::handle::{Handle, PubKeyHash};
 
 /**
 Bearer predicate invariant clause for token type.
@@ -73,7 +71,7 @@ in the "transfer NFT" transaction for the predicate must be empty).
 #[cfg(feature = "type-bearer")]
 #[unsafe(no_mangle)]
 pub extern "C" fn type_bearer() -> u64 {
-    let cfg = match Config::load() {
+    let cfg = match evaluation_ctx::config::<Config>() {
         Ok(c) => c,
         Err(err) => predicate_result!(err.chain(0xc)),
     };
@@ -88,8 +86,8 @@ pub extern "C" fn type_bearer() -> u64 {
                 _ => return 3,
             };
             let cur_dt = evaluation_ctx::current_time();
-            if (status == b"early-bird" && cur_dt <= cfg.early_bird_date)
-                || (status == b"regular" && cur_dt <= cfg.transferrable_until)
+            if (status == b"early-bird" && cur_dt <= cfg.terms.early_bird_date)
+                || (status == b"regular" && cur_dt <= cfg.terms.transferrable_until)
             {
                 predicate_result!(true)
             }
@@ -191,7 +189,7 @@ flag which saves the transfer proof(s) in this format.
 #[cfg(feature = "token-bearer")]
 #[unsafe(no_mangle)]
 pub extern "C" fn token_bearer() -> u64 {
-    let cfg = match Config::load() {
+    let cfg = match evaluation_ctx::config::<Config>() {
         Ok(c) => c,
         Err(err) => predicate_result!(err.chain(0xc)),
     };
@@ -219,8 +217,8 @@ pub extern "C" fn token_bearer() -> u64 {
     nonce_data.append(&mut txo.unit_id);
     let transferred = check_money_transfer(cfg.pkh, nonce_data);
 
-    if (status == b"early-bird" && transferred == cfg.early_bird_price)
-        || (status == b"regular" && transferred == cfg.regular_price)
+    if (status == b"early-bird" && transferred == cfg.terms.early_bird_price)
+        || (status == b"regular" && transferred == cfg.terms.regular_price)
     {
         predicate_result!(true)
     }
@@ -255,7 +253,7 @@ flag which saves the transfer proof(s) in this format.
 #[cfg(feature = "token-update-data")]
 #[unsafe(no_mangle)]
 pub extern "C" fn token_update_data() -> u64 {
-    let cfg = match Config::load() {
+    let cfg = match evaluation_ctx::config::<Config>() {
         Ok(c) => c,
         Err(err) => predicate_result!(err.chain(0xc)),
     };
@@ -272,7 +270,7 @@ pub extern "C" fn token_update_data() -> u64 {
 
     // date <= D2
     let cur_dt = evaluation_ctx::current_time();
-    if cur_dt > cfg.transferrable_until {
+    if cur_dt > cfg.terms.transferrable_until {
         predicate_result!(false, 1)
     }
     // receipt of payment of P2-P1 to the conference organizer
@@ -280,7 +278,7 @@ pub extern "C" fn token_update_data() -> u64 {
     let mut nonce_data: Vec<u8> = alloc::vec![0x02];
     nonce_data.append(&mut txo.unit_id);
     let transferred = check_money_transfer(cfg.pkh, nonce_data);
-    if transferred != cfg.regular_price - cfg.early_bird_price {
+    if transferred != cfg.terms.regular_price - cfg.terms.early_bird_price {
         predicate_result!(false, 7)
     }
     predicate_result!(true)
@@ -291,7 +289,7 @@ checks that the money transfer transaction(s) sent in as predicate argument
 is valid and returns the amount transferred.
 */
 #[cfg(any(feature = "token-update-data", feature = "token-bearer"))]
-fn check_money_transfer(pkh: ABHandle, nonce_data: Vec<u8>) -> u64 {
+fn check_money_transfer(pkh: Handle<PubKeyHash>, nonce_data: Vec<u8>) -> u64 {
     let ref_no = alphabill::api::digest_sha256(nonce_data);
     alphabill::api::amount_transferred(evaluation_ctx::HANDLE_ARGUMENT, pkh, Some(&ref_no))
 }
@@ -301,77 +299,43 @@ fn check_money_transfer(pkh: ABHandle, nonce_data: Vec<u8>) -> u64 {
     feature = "token-bearer",
     feature = "type-bearer"
 ))]
+#[derive(PredicateConfig)]
 struct Config {
-    #[cfg(any(feature = "type-bearer"))]
+    terms: Terms,
+
+    #[cfg(any(feature = "token-update-data", feature = "token-bearer"))]
+    /// public key hash of the organizer.
+    /// At this time only the p2pkh template is supported so we store
+    /// the hash rather than public key
+    #[config(handle, index = 1)]
+    pkh: Handle<PubKeyHash>,
+}
+
+#[cfg(any(
+    feature = "token-update-data",
+    feature = "token-bearer",
+    feature = "type-bearer"
+))]
+#[derive(PredicateConfig)]
+#[config(len = 4)]
+struct Terms {
+    #[cfg(feature = "type-bearer")]
     /// after this date early-bird price ends
+    #[config(index = 0)]
     early_bird_date: u64,
 
     #[cfg(any(feature = "token-update-data", feature = "type-bearer"))]
     /// tickets can be transferred until this date
+    #[config(index = 1)]
     transferrable_until: u64,
 
     #[cfg(any(feature = "token-update-data", feature = "token-bearer"))]
     /// early-bird price
+    #[config(index = 2)]
     early_bird_price: u64,
 
     #[cfg(any(feature = "token-update-data", feature = "token-bearer"))]
     /// regular ticket price
+    #[config(index = 3)]
     regular_price: u64,
-
-    #[cfg(any(feature = "token-update-data", feature = "token-bearer"))]
-    /// public key hash of the organizer.
-    /// At this time only the p2pkh template is supported so we store
-    /// the hash rather than public key
-    pkh: ABHandle,
-}
-
-#[cfg(any(
-    feature = "token-update-data",
-    feature = "token-bearer",
-    feature = "type-bearer"
-))]
-impl Config {
-    fn load() -> Result<Self, Error> {
-        let cfg = cbor::parse_array(evaluation_ctx::HANDLE_CONFIG);
-        Self::from(cfg)
-    }
-
-    fn from(cfg: &[ABHandle]) -> Result<Self, Error> {
-        let mut p = decoder::Decoder::from_handle(cfg[0]);
-        let v = p.value();
-        if let Value::Array(items) = v {
-            if items.len() != 4 {
-                return Err(Error::new(7));
-            }
-            return Ok(Config {
-                #[cfg(any(feature = "type-bearer"))]
-                early_bird_date: match items[0] {
-                    Value::U64(v) => v,
-                    Value::U32(v) => v.into(),
-                    _ => return Err(Error::new(1)),
-                },
-                #[cfg(any(feature = "token-update-data", feature = "type-bearer"))]
-                transferrable_until: match items[1] {
-                    Value::U64(v) => v,
-                    Value::U32(v) => v.into(),
-                    _ => return Err(Error::new(2)),
-                },
-                #[cfg(any(feature = "token-update-data", feature = "token-bearer"))]
-                early_bird_price: match items[2] {
-                    Value::U64(v) => v,
-                    Value::U32(v) => v.into(),
-                    _ => return Err(Error::new(3)),
-                },
-                #[cfg(any(feature = "token-update-data", feature = "token-bearer"))]
-                regular_price: match items[3] {
-                    Value::U64(v) => v,
-                    Value::U32(v) => v.into(),
-                    _ => return Err(Error::new(4)),
-                },
-                #[cfg(any(feature = "token-update-data", feature = "token-bearer"))]
-                pkh: cfg[1],
-            });
-        }
-        Err(Error::new(6))
-    }
 }
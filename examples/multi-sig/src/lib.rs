@@ -1,11 +1,16 @@
-#![no_std]
+#![cfg_attr(not(test), no_std)]
+
+extern crate alloc;
+use alloc::vec::Vec;
 
 use alphabill::{
+    PredicateConfig,
     api::{SignedByResult, signed_by_pkh},
     cbor,
-    decoder::{Decoder, Value},
-    error::Error,
-    evaluation_ctx, predicate_result,
+    error::error_codes,
+    evaluation_ctx,
+    handle::{Handle, PubKeyHash},
+    predicate_result,
 };
 
 /**
@@ -39,33 +44,32 @@ valid signatures to satisfy the threshold (IOW invalid signature is interpreted
  - `1`: predicate evaluates to "false" (too many signatures missing, early exit);
  - `0xnn01`: false because P2PKH evaluates to "false" or error;
  - `0xff01`: false because not enough positive votes (invalid threshold?);
- - `0x0c`: failed to load threshold configuration;
- - `0x1c`: number of proofs does not equal to number of PKH-s;
+ - errors listed by [`MultiSigError`];
 */
 #[unsafe(no_mangle)]
 pub extern "C" fn multi_sig() -> u64 {
     // load configuration
-    let cfg = cbor::parse_array(evaluation_ctx::HANDLE_CONFIG);
-    // read the required signature count value
-    let mut p = Decoder::from_handle(cfg[0]);
-    let threshold = match p.value() {
-        Value::U64(v) => v as u8,
-        Value::U32(v) => v as u8,
-        _ => predicate_result!(Error::new(0x0C)),
+    let cfg: Config = match evaluation_ctx::config() {
+        Ok(cfg) => cfg,
+        Err(err) => predicate_result!(MultiSigError::Config.chain(err)),
     };
-
-    let pkh_handles = &cfg[1..];
+    let threshold = cfg.threshold;
+    let pkh_handles = &cfg.pkh;
     // prepare the proof(s)
     let proof_handles = cbor::parse_array(evaluation_ctx::HANDLE_ARGUMENT);
     if proof_handles.len() != pkh_handles.len() {
-        predicate_result!(Error::new(0x1C));
+        predicate_result!(MultiSigError::ProofCount.error());
     }
 
     // iterate over pkh/proofs and verify
     let mut missing_allowed = pkh_handles.len() - (threshold as usize);
     let mut valid_signatures: u8 = 0;
     for (i, pkh) in pkh_handles.iter().enumerate() {
-        match signed_by_pkh(evaluation_ctx::HANDLE_TX_ORDER, *pkh, proof_handles[i]) {
+        match signed_by_pkh(
+            evaluation_ctx::HANDLE_TX_ORDER,
+            *pkh,
+            proof_handles[i].proof(),
+        ) {
             SignedByResult::True => valid_signatures += 1,
             SignedByResult::NilProof => {
                 // if it's no longer possible to get threshold valid signatures break early
@@ -74,7 +78,7 @@ pub extern "C" fn multi_sig() -> u64 {
                 }
                 missing_allowed -= 1;
             }
-            err => predicate_result!(false, err as u64),
+            err => predicate_result!(false, err.code()),
         };
     }
 
@@ -83,3 +87,79 @@ pub extern "C" fn multi_sig() -> u64 {
     }
     predicate_result!(false, 0xff)
 }
+
+error_codes! {
+    /// Errors returned by the [`multi_sig`] predicate.
+    pub enum MultiSigError {
+        Config = 0x0c => "failed to load configuration",
+        ProofCount = 0x1c => "number of proofs does not equal to number of PKH-s",
+    }
+}
+
+#[derive(PredicateConfig)]
+struct Config {
+    /// number of valid signatures required
+    threshold: u8,
+    #[config(handle, rest)]
+    pkh: Vec<Handle<PubKeyHash>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alphabill::host::mock::MockHost;
+
+    // CBOR encoded configuration [2, h'0101', h'0202', h'0303']
+    const CONFIG: [u8; 11] = [0x84, 0x02, 0x42, 1, 1, 0x42, 2, 2, 0x42, 3, 3];
+
+    fn host(argument: &[u8]) -> MockHost {
+        MockHost::new()
+            .config(&CONFIG)
+            .argument(argument)
+            .tx_signed_by_pkh(&[1, 1], &[0xA1], SignedByResult::True)
+            .tx_signed_by_pkh(&[2, 2], &[0xA2], SignedByResult::True)
+            .tx_signed_by_pkh(&[3, 3], &[0xA3], SignedByResult::True)
+    }
+
+    #[test]
+    fn threshold_reached() {
+        // [h'A1', nil, h'A3']
+        host(&[0x83, 0x41, 0xA1, 0xF6, 0x41, 0xA3]).install();
+        assert_eq!(multi_sig(), 0);
+
+        // [h'A1', h'A2', h'A3']
+        host(&[0x83, 0x41, 0xA1, 0x41, 0xA2, 0x41, 0xA3]).install();
+        assert_eq!(multi_sig(), 0);
+    }
+
+    #[test]
+    fn too_many_missing() {
+        // [h'A1', nil, nil]
+        host(&[0x83, 0x41, 0xA1, 0xF6, 0xF6]).install();
+        assert_eq!(multi_sig(), 1);
+    }
+
+    #[test]
+    fn invalid_signature() {
+        // [h'A1', h'A2', h'FF'] - last signature is not valid
+        host(&[0x83, 0x41, 0xA1, 0x41, 0xA2, 0x41, 0xFF]).install();
+        assert_eq!(multi_sig(), 0x0101);
+    }
+
+    #[test]
+    fn proof_count_mismatch() {
+        // [h'A1', h'A2']
+        host(&[0x82, 0x41, 0xA1, 0x41, 0xA2]).install();
+        assert_eq!(multi_sig(), 0x1c);
+    }
+
+    #[test]
+    fn invalid_config() {
+        // [h'02', h'0101']
+        MockHost::new()
+            .config(&[0x82, 0x41, 0x02, 0x42, 1, 1])
+            .argument(&[0x81, 0x41, 0xA1])
+            .install();
+        assert_eq!(multi_sig(), 0x07010c);
+    }
+}
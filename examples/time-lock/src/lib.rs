@@ -1,10 +1,11 @@
-#![no_std]
+#![cfg_attr(not(test), no_std)]
 
 use alphabill::{
+    PredicateConfig,
     api::{SignedByResult, signed_by_pkh},
-    cbor,
-    decoder::{Decoder, Value},
-    evaluation_ctx, predicate_result,
+    evaluation_ctx,
+    handle::{Handle, PubKeyHash},
+    predicate_result,
 };
 
 /**
@@ -29,29 +30,87 @@ uses.
  - `0`: predicate evaluates to "true";
  - `0xff01`: predicate evaluates to "false" because current time is not past unlock time;
  - `0xnn01`: false because evaluating P2PKH returned false or error;
- - `0x0c`: failed to load locked_until date from configuration (not uint64?);
+ - `0x0c01`: failed to load configuration (locked_until date is not uint64?);
 */
 #[unsafe(no_mangle)]
 pub extern "C" fn time_lock() -> u64 {
-    let cfg_handles = cbor::parse_array(evaluation_ctx::HANDLE_CONFIG);
-    // first handle refers to the locked until date
-    let mut p = Decoder::from_handle(cfg_handles[0]);
-    let locked_until = match p.value() {
-        Value::U64(v) => v,
-        Value::U32(v) => v as u64,
-        _ => predicate_result!(false, 0x0c),
+    let cfg: Config = match evaluation_ctx::config() {
+        Ok(cfg) => cfg,
+        Err(_) => predicate_result!(false, 0x0c),
     };
 
-    if evaluation_ctx::current_time() < locked_until {
+    if evaluation_ctx::current_time() < cfg.locked_until {
         predicate_result!(false, 0xff)
     }
 
     match signed_by_pkh(
         evaluation_ctx::HANDLE_TX_ORDER,
-        cfg_handles[1],
+        cfg.pkh,
         evaluation_ctx::HANDLE_ARGUMENT,
     ) {
         SignedByResult::True => predicate_result!(true),
-        err => predicate_result!(false, err as u64),
+        err => predicate_result!(false, err.code()),
+    }
+}
+
+#[derive(PredicateConfig)]
+struct Config {
+    /// Unix time until which the predicate evaluates to "false"
+    locked_until: u64,
+    /// public key hash of the bearer
+    #[config(handle)]
+    pkh: Handle<PubKeyHash>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alphabill::host::mock::MockHost;
+
+    const PKH: [u8; 4] = [1, 2, 3, 4];
+    const PROOF: [u8; 3] = [0xA, 0xB, 0xC];
+
+    // CBOR encoded configuration [1709683200, h'01020304']
+    const CONFIG: [u8; 11] = [0x82, 0x1A, 0x65, 0xE7, 0xB2, 0x00, 0x44, 1, 2, 3, 4];
+
+    #[test]
+    fn locked() {
+        MockHost::new()
+            .config(&CONFIG)
+            .argument(&PROOF)
+            .current_time(1709683199)
+            .tx_signed_by_pkh(&PKH, &PROOF, SignedByResult::True)
+            .install();
+        assert_eq!(time_lock(), 0xff01);
+    }
+
+    #[test]
+    fn unlocked() {
+        MockHost::new()
+            .config(&CONFIG)
+            .argument(&PROOF)
+            .current_time(1709683200)
+            .tx_signed_by_pkh(&PKH, &PROOF, SignedByResult::True)
+            .install();
+        assert_eq!(time_lock(), 0);
+    }
+
+    #[test]
+    fn not_signed_by_owner() {
+        MockHost::new()
+            .config(&CONFIG)
+            .argument(&PROOF)
+            .current_time(1709683201)
+            .install();
+        assert_eq!(time_lock(), 0x0101);
+    }
+
+    #[test]
+    fn invalid_config() {
+        // unlock time is encoded as byte string
+        MockHost::new()
+            .config(&[0x82, 0x41, 0x01, 0x44, 1, 2, 3, 4])
+            .install();
+        assert_eq!(time_lock(), 0x0c01);
     }
 }
@@ -5,18 +5,44 @@ Alphabill specific APIs, ie to verify units etc.
 extern crate alloc;
 use alloc::vec::Vec;
 
-use crate::{evaluation_ctx::ABHandle, memory};
+use crate::{
+    PredicateResult, Verdict,
+    error::Error,
+    handle::{self, Handle, Proof, PubKeyHash},
+    memory::{self, HostBuf},
+};
+
+#[cfg(not(feature = "mock-host"))]
+use crate::evaluation_ctx::ABHandle;
 
 /**
 Returns the SHA256 checksum of the data.
 */
-pub fn digest_sha256(data: Vec<u8>) -> Vec<u8> {
-    let p = data.as_ptr();
-    let mut addr = crate::memory::pack_pointer((p as usize) as u32, data.len());
-    addr = unsafe { _digest_sha256(addr) };
-    crate::memory::load_bytes(addr)
+pub fn digest_sha256(data: Vec<u8>) -> HostBuf {
+    let addr = memory::pack_slice(&data);
+    unsafe { HostBuf::from_raw(_digest_sha256(addr)) }
+}
+
+/**
+Outcome of the [`signed_by_pkh`] check.
+
+The [`code`](SignedByResult::code) of the result is the status code returned
+by the host so it is stable and can be used as the "false" reason of the
+predicate, ie
+```
+use alphabill::{api::SignedByResult, predicate_result};
+
+fn check(r: SignedByResult) -> u64 {
+    match r {
+        SignedByResult::True => predicate_result!(true),
+        err => predicate_result!(false, err.code()),
+    }
 }
 
+assert_eq!(check(SignedByResult::NilProof), 0x0701);
+```
+*/
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum SignedByResult {
     True,
     False,
@@ -26,24 +52,227 @@ pub enum SignedByResult {
     InvalidHandleProof,
     NilPKH,
     NilProof,
+    /// status code not known to this version of the SDK
+    Unknown(u32),
+}
+
+impl SignedByResult {
+    /// Returns the host status code of the result.
+    pub fn code(&self) -> u64 {
+        let code = match self {
+            SignedByResult::True => 0,
+            SignedByResult::False => 1,
+            SignedByResult::P2PKHError => 2,
+            SignedByResult::InvalidHandleTxO => 3,
+            SignedByResult::InvalidHandlePKH => 4,
+            SignedByResult::InvalidHandleProof => 5,
+            SignedByResult::NilPKH => 6,
+            SignedByResult::NilProof => 7,
+            SignedByResult::Unknown(c) => *c,
+        };
+        code as u64
+    }
 }
 
-pub fn signed_by_pkh(txo: ABHandle, pkh: ABHandle, proof: ABHandle) -> SignedByResult {
-    let code = unsafe { _tx_signed_by_pkh(txo, pkh, proof) };
-    //unsafe { core::mem::transmute(code as u8) }
-    match code {
-        0 => SignedByResult::True,
-        1 => SignedByResult::False,
-        2 => SignedByResult::P2PKHError,
-        3 => SignedByResult::InvalidHandleTxO,
-        4 => SignedByResult::InvalidHandlePKH,
-        5 => SignedByResult::InvalidHandleProof,
-        6 => SignedByResult::NilPKH,
-        7 => SignedByResult::NilProof,
-        _ => unreachable!(),
+impl From<u32> for SignedByResult {
+    fn from(code: u32) -> Self {
+        match code {
+            0 => SignedByResult::True,
+            1 => SignedByResult::False,
+            2 => SignedByResult::P2PKHError,
+            3 => SignedByResult::InvalidHandleTxO,
+            4 => SignedByResult::InvalidHandlePKH,
+            5 => SignedByResult::InvalidHandleProof,
+            6 => SignedByResult::NilPKH,
+            7 => SignedByResult::NilProof,
+            c => SignedByResult::Unknown(c),
+        }
     }
 }
 
+/// The status code is used as error code, codes above `0xFF` are saturated.
+impl From<SignedByResult> for Error {
+    fn from(r: SignedByResult) -> Self {
+        Error::new(u8::try_from(r.code()).unwrap_or(u8::MAX))
+    }
+}
+
+/// "True" or "false" with the status code as the reason.
+impl PredicateResult for SignedByResult {
+    fn into_u64(self) -> u64 {
+        match self {
+            SignedByResult::True => crate::predicate_result!(true),
+            r => crate::predicate_result!(false, r.code()),
+        }
+    }
+}
+
+pub fn signed_by_pkh(
+    txo: Handle<handle::TxOrder>,
+    pkh: Handle<PubKeyHash>,
+    proof: Handle<Proof>,
+) -> SignedByResult {
+    SignedByResult::from(unsafe { _tx_signed_by_pkh(txo.raw(), pkh.raw(), proof.raw()) })
+}
+
+/// Outcome of the signature verification, see [`verify_secp256k1`] and [`verify_ed25519`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum VerifyResult {
+    True,
+    False,
+    InvalidPubKey,
+    InvalidSignature,
+    InvalidMsgHash,
+    /// status code not known to this version of the SDK
+    Unknown(u32),
+}
+
+impl VerifyResult {
+    /// Returns the host status code of the result.
+    pub fn code(&self) -> u64 {
+        let code = match self {
+            VerifyResult::True => 0,
+            VerifyResult::False => 1,
+            VerifyResult::InvalidPubKey => 2,
+            VerifyResult::InvalidSignature => 3,
+            VerifyResult::InvalidMsgHash => 4,
+            VerifyResult::Unknown(c) => *c,
+        };
+        code as u64
+    }
+}
+
+impl From<u32> for VerifyResult {
+    fn from(code: u32) -> Self {
+        match code {
+            0 => VerifyResult::True,
+            1 => VerifyResult::False,
+            2 => VerifyResult::InvalidPubKey,
+            3 => VerifyResult::InvalidSignature,
+            4 => VerifyResult::InvalidMsgHash,
+            c => VerifyResult::Unknown(c),
+        }
+    }
+}
+
+/// The status code is used as error code, codes above `0xFF` are saturated.
+impl From<VerifyResult> for Error {
+    fn from(r: VerifyResult) -> Self {
+        Error::new(u8::try_from(r.code()).unwrap_or(u8::MAX))
+    }
+}
+
+/// "True" or "false" with the status code as the reason.
+impl PredicateResult for VerifyResult {
+    fn into_u64(self) -> u64 {
+        match self {
+            VerifyResult::True => crate::predicate_result!(true),
+            r => crate::predicate_result!(false, r.code()),
+        }
+    }
+}
+
+/**
+Verifies secp256k1 ECDSA signature of the message hash.
+# Parameters
+- pubkey: public key of the signer in SEC1 (compressed or uncompressed) encoding;
+- msg_hash: 32 byte hash of the signed message, ie [`digest_sha256`] of it;
+- sig: signature as `r || s`, optionally followed by the recovery ID byte;
+*/
+pub fn verify_secp256k1(pubkey: &[u8], msg_hash: &[u8], sig: &[u8]) -> VerifyResult {
+    let code = unsafe {
+        _verify_secp256k1(
+            memory::pack_slice(pubkey),
+            memory::pack_slice(msg_hash),
+            memory::pack_slice(sig),
+        )
+    };
+    VerifyResult::from(code)
+}
+
+/**
+Verifies secp256k1 ECDSA signature of the message, the SHA256 hash of the message
+is calculated in the module and then verified with [`verify_secp256k1`].
+*/
+#[cfg(feature = "sha256")]
+pub fn verify_secp256k1_sha256(pubkey: &[u8], msg: &[u8], sig: &[u8]) -> VerifyResult {
+    use sha2::{Digest, Sha256};
+
+    verify_secp256k1(pubkey, &Sha256::digest(msg), sig)
+}
+
+/**
+Verifies secp256k1 ECDSA signature of the message, the SHA256 hash of the message
+is calculated by the host (see [`digest_sha256`]) and then verified with
+[`verify_secp256k1`]. Enable the `sha256` feature to hash in the module instead.
+*/
+#[cfg(not(feature = "sha256"))]
+pub fn verify_secp256k1_sha256(pubkey: &[u8], msg: &[u8], sig: &[u8]) -> VerifyResult {
+    let hash = unsafe { HostBuf::from_raw(_digest_sha256(memory::pack_slice(msg))) };
+    verify_secp256k1(pubkey, &hash, sig)
+}
+
+/**
+Verifies Ed25519 signature of the message.
+# Parameters
+- pubkey: 32 byte public key of the signer;
+- msg: the signed message;
+- sig: 64 byte signature;
+*/
+pub fn verify_ed25519(pubkey: &[u8], msg: &[u8], sig: &[u8]) -> VerifyResult {
+    let code = unsafe {
+        _verify_ed25519(
+            memory::pack_slice(pubkey),
+            memory::pack_slice(msg),
+            memory::pack_slice(sig),
+        )
+    };
+    VerifyResult::from(code)
+}
+
+/**
+Outcome of evaluating a predicate, see [`satisfies`].
+
+The value returned by the predicate is decoded by [`Verdict::from_u64`].
+*/
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum EvalResult {
+    True,
+    /// predicate evaluated to "false", the informational code returned by the predicate
+    False(u64),
+    /// evaluation of the predicate failed, the error code
+    Error(u64),
+}
+
+impl From<u64> for EvalResult {
+    fn from(v: u64) -> Self {
+        match Verdict::from_u64(v) {
+            Verdict::True => EvalResult::True,
+            Verdict::False(code) => EvalResult::False(code),
+            Verdict::Error(err) => EvalResult::Error(err.code()),
+        }
+    }
+}
+
+/**
+Evaluates predicate against the transaction on the host.
+
+Any predicate supported by Alphabill can be evaluated, ie built-in templates
+(always-true, always-false, P2PKH) or WASM predicate.
+# Parameters
+- txo: handle of the transaction order;
+- predicate: CBOR encoded Alphabill predicate record;
+- proof: handle of the argument (proof) for the predicate;
+*/
+pub fn satisfies(
+    txo: Handle<handle::TxOrder>,
+    predicate: &[u8],
+    proof: Handle<Proof>,
+) -> EvalResult {
+    let addr = memory::pack_slice(predicate);
+    EvalResult::from(unsafe { _evaluate_predicate(txo.raw(), addr, proof.raw()) })
+}
+
 /**
 Verify that money transfer has taken place.
 # Parameters
@@ -52,28 +281,62 @@ Verify that money transfer has taken place.
 - ref_no: if given the transfer(s) must have the given reference number;
 */
 pub fn amount_transferred(
-    tx_proof: ABHandle,
-    receiver_pkh: ABHandle,
+    tx_proof: Handle<Proof>,
+    receiver_pkh: Handle<PubKeyHash>,
     ref_no: Option<&[u8]>,
 ) -> u64 {
     let addr_rn = match ref_no {
         None => 0,
-        Some(d) => {
-            let p = d.as_ptr();
-            memory::pack_pointer((p as usize) as u32, d.len())
-        }
+        Some(d) => memory::pack_slice(d),
     };
-    unsafe { _amount_transferred(tx_proof, receiver_pkh, addr_rn) }
+    unsafe { _amount_transferred(tx_proof.raw(), receiver_pkh.raw(), addr_rn) }
+}
+
+/**
+Verifies that the transaction proof is valid proof of the transaction record,
+ie the transaction has been executed and the block containing it is certified.
+
+Both `proof` and `record` must be handles of native Alphabill objects, see
+[`TxRecordProof`](crate::txsystem::TxRecordProof).
+
+Returns error when the host fails to verify the proof (ie invalid handles), the
+host's error code is used as error code.
+*/
+pub fn verify_tx_proof(
+    proof: Handle<handle::TxProof>,
+    record: Handle<handle::TxRecord>,
+) -> Result<bool, Error> {
+    match unsafe { _verify_tx_proof(proof.raw(), record.raw()) } {
+        0 => Ok(true),
+        1 => Ok(false),
+        code => Err(Error::new(u8::try_from(code).unwrap_or(u8::MAX))),
+    }
 }
 
+#[cfg(not(feature = "mock-host"))]
 #[link(wasm_import_module = "ab")]
 unsafe extern "C" {
     #[link_name = "amount_transferred"]
     fn _amount_transferred(tx_proof: ABHandle, pkh: ABHandle, ref_no: u64) -> u64;
 
+    #[link_name = "evaluate_predicate"]
+    fn _evaluate_predicate(txo: ABHandle, predicate: u64, proof: ABHandle) -> u64;
+
     #[link_name = "tx_signed_by_pkh"]
     fn _tx_signed_by_pkh(txo: ABHandle, pkh: ABHandle, proof: ABHandle) -> u32;
 
+    #[link_name = "verify_tx_proof"]
+    fn _verify_tx_proof(proof: ABHandle, record: ABHandle) -> u32;
+
+    #[link_name = "verify_secp256k1"]
+    fn _verify_secp256k1(pubkey: u64, msg_hash: u64, sig: u64) -> u32;
+
+    #[link_name = "verify_ed25519"]
+    fn _verify_ed25519(pubkey: u64, msg: u64, sig: u64) -> u32;
+
     #[link_name = "digest_sha256"]
     fn _digest_sha256(addr: u64) -> u64;
 }
+
+#[cfg(feature = "mock-host")]
+use crate::host::mock::ab::*;
@@ -1,8 +1,42 @@
 /*!
 Some basic CBOR handling support.
+
+There are two ways to work with CBOR encoded data:
+ - host side parsing: the [`parse`], [`parse_array`] and [`chunks`] functions
+   register the decoded items as host variables and return handles to them;
+ - in predicate parsing: the [`decode`] function decodes the data into a tree of
+   [`Item`]s in the predicate's memory and the [`Encoder`] can be used to build
+   CBOR encoded data (ie to create host variable with [`add_var`]).
+
+Decoding in the predicate is usually cheaper than multiple host calls, ie
+```
+use alphabill::cbor::{self, Item};
+
+// [100, h'0102', null]
+let cfg = cbor::decode(&[0x83, 0x18, 0x64, 0x42, 0x01, 0x02, 0xf6]).unwrap();
+let items = cfg.as_array().unwrap();
+assert_eq!(items[0].as_u64(), Some(100));
+assert_eq!(items[1].as_bytes(), Some(&[1u8, 2][..]));
+assert!(items[2].is_null());
+
+let data = cbor::Encoder::new().array(2).uint(100).bytes(&[1, 2]).finish();
+assert_eq!(data, [0x82, 0x18, 0x64, 0x42, 0x01, 0x02]);
+```
+
+[`add_var`]: crate::evaluation_ctx::add_var
 */
-use crate::memory;
+extern crate alloc;
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+use crate::{
+    decoder::{ERR_INVALID_UTF8, ERR_TRUNCATED},
+    error::Error,
+    evaluation_ctx,
+    handle::{Bytes, Handle, RawData},
+    memory::HostBuf,
+};
 
+#[cfg(not(feature = "mock-host"))]
 use crate::evaluation_ctx::ABHandle;
 
 const FLAG_ARRAY: u32 = 0;
@@ -18,15 +52,16 @@ When the CBOR encoded buffer is not a single struct (Alphabill serializes
 structs to CBOR as arrays) then the [`parse_array`] can be used to get handle
 of each individual element.
 
+[`ERR_MISSING_ITEM`] is returned when the host doesn't return any handle, ie
+the `handle` is not valid or the data is not valid CBOR.
+
 [`Decoder`]: crate::decoder::Decoder
 [`serialize_obj`]: crate::evaluation_ctx::serialize_obj
 */
-pub fn parse(handle: ABHandle) -> ABHandle {
-    let addr = unsafe { _parse(handle, FLAG_STRUCT) };
-    let (addr, size) = memory::unpack_pointer(addr);
-    let data = addr as *const ABHandle;
-    let handles = unsafe { core::slice::from_raw_parts(data, size / size_of::<ABHandle>()) };
-    handles[0]
+pub fn parse<T: RawData>(handle: Handle<T>) -> Result<Handle<Bytes>, Error> {
+    let buf: HostBuf<Handle<Bytes>> =
+        unsafe { HostBuf::from_raw(_parse(handle.raw(), FLAG_STRUCT)) };
+    buf.first().copied().ok_or(Error::new(ERR_MISSING_ITEM))
 }
 
 /**
@@ -36,11 +71,8 @@ This function acts the same way as [parse] except when the `handle` points
 to (CBOR encoded) array - then it registers each (decoded) array item as
 individual host variable and returns handle to it.
 */
-pub fn parse_array<'a>(handle: ABHandle) -> &'a [ABHandle] {
-    let ptr = unsafe { _parse(handle, FLAG_ARRAY) };
-    let (addr, size) = memory::unpack_pointer(ptr);
-    let data = addr as *const ABHandle;
-    unsafe { core::slice::from_raw_parts(data, size / size_of::<ABHandle>()) }
+pub fn parse_array<T: RawData>(handle: Handle<T>) -> HostBuf<Handle<Bytes>> {
+    unsafe { HostBuf::from_raw(_parse(handle.raw(), FLAG_ARRAY)) }
 }
 
 /**
@@ -53,13 +85,492 @@ objects based on the data.
 
 [`create_obj`]: crate::evaluation_ctx::create_obj
 */
-pub fn chunks<'a>(handle: ABHandle) -> &'a [ABHandle] {
-    let ptr = unsafe { _chunks(handle) };
-    let (addr, size) = memory::unpack_pointer(ptr);
-    let data = addr as *const ABHandle;
-    unsafe { core::slice::from_raw_parts(data, size / size_of::<ABHandle>()) }
+pub fn chunks<T: RawData>(handle: Handle<T>) -> HostBuf<Handle<Bytes>> {
+    unsafe { HostBuf::from_raw(_chunks(handle.raw())) }
+}
+
+/// error code returned when the data is not valid CBOR (ie reserved values are used).
+pub const ERR_MALFORMED: u8 = 5;
+/// error code returned when the data is nested deeper than [`MAX_DEPTH`].
+pub const ERR_TOO_DEEP: u8 = 6;
+
+/// error code returned when the item is not of expected type (or value is out of range).
+pub const ERR_INVALID_TYPE: u8 = 7;
+/// error code returned when required item is missing.
+pub const ERR_MISSING_ITEM: u8 = 8;
+
+/// maximum nesting depth of arrays, maps and tags [`decode`] accepts.
+pub const MAX_DEPTH: usize = 16;
+
+/**
+CBOR data item.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub enum Item {
+    /// major type 0, unsigned integer
+    Uint(u64),
+    /// major type 1, negative integer, the value is `-1 - n`
+    NegInt(u64),
+    /// major type 2, byte string
+    Bytes(Vec<u8>),
+    /// major type 3, text string
+    Text(String),
+    /// major type 4, array of data items
+    Array(Vec<Item>),
+    /// major type 5, map of pairs of data items
+    Map(Vec<(Item, Item)>),
+    /// major type 6, tagged data item
+    Tag(u64, Box<Item>),
+    Bool(bool),
+    /// CBOR `null`, ie Go's `nil`
+    Null,
+    Undefined,
+    /// simple value other than bool, null or undefined
+    Simple(u8),
+    Float(f64),
+}
+
+impl Item {
+    /// returns the value of unsigned integer item.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Item::Uint(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// returns the content of the byte string item.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Item::Bytes(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// returns the content of the text string item.
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Item::Text(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// returns the items of the array item.
+    pub fn as_array(&self) -> Option<&[Item]> {
+        match self {
+            Item::Array(a) => Some(a),
+            _ => None,
+        }
+    }
+
+    /// returns true when item is `null` (or `undefined`).
+    pub fn is_null(&self) -> bool {
+        matches!(self, Item::Null | Item::Undefined)
+    }
+
+    /// returns the item encoded as CBOR.
+    pub fn encode(&self) -> Vec<u8> {
+        Encoder::new().item(self).finish()
+    }
+}
+
+/**
+Decodes CBOR encoded data item, the data must not contain anything after the item.
+
+Both definite and indefinite length strings, arrays and maps are supported.
+*/
+pub fn decode(data: &[u8]) -> Result<Item, Error> {
+    let mut p = Parser { data, pos: 0 };
+    let item = p.item(0)?;
+    if p.pos != data.len() {
+        return Err(Error::new(ERR_MALFORMED));
+    }
+    Ok(item)
+}
+
+/**
+Decodes the raw CBOR data of the host variable, ie [`HANDLE_CONFIG`] or [`HANDLE_ARGUMENT`].
+
+[`HANDLE_CONFIG`]: crate::evaluation_ctx::HANDLE_CONFIG
+[`HANDLE_ARGUMENT`]: crate::evaluation_ctx::HANDLE_ARGUMENT
+*/
+pub fn decode_var<T: RawData>(handle: Handle<T>) -> Result<Item, Error> {
+    let data = evaluation_ctx::var_data(handle)?;
+    decode(&data)
+}
+
+/**
+Creates host variable with the value of the item and returns handle to it.
+
+For byte string items the content of the string is used as the value of the
+variable, any other item is stored as CBOR encoded data.
+*/
+pub fn add_var(item: &Item) -> Handle<Bytes> {
+    match item {
+        Item::Bytes(b) => evaluation_ctx::add_var(b.clone()),
+        _ => evaluation_ctx::add_var(item.encode()),
+    }
+}
+
+/**
+Type which can be created from CBOR data item.
+
+Implemented by the [`PredicateConfig`] derive macro.
+
+[`PredicateConfig`]: crate::PredicateConfig
+*/
+pub trait FromItem: Sized {
+    fn from_item(item: &Item) -> Result<Self, Error>;
+}
+
+impl FromItem for Item {
+    fn from_item(item: &Item) -> Result<Self, Error> {
+        Ok(item.clone())
+    }
+}
+
+impl FromItem for u64 {
+    fn from_item(item: &Item) -> Result<Self, Error> {
+        item.as_u64().ok_or(Error::new(ERR_INVALID_TYPE))
+    }
+}
+
+macro_rules! from_item_uint {
+    ($($t:ty),*) => {$(
+        impl FromItem for $t {
+            fn from_item(item: &Item) -> Result<Self, Error> {
+                <$t>::try_from(u64::from_item(item)?).or(Err(Error::new(ERR_INVALID_TYPE)))
+            }
+        }
+    )*};
+}
+
+from_item_uint!(u8, u16, u32);
+
+impl FromItem for bool {
+    fn from_item(item: &Item) -> Result<Self, Error> {
+        match item {
+            Item::Bool(v) => Ok(*v),
+            _ => Err(Error::new(ERR_INVALID_TYPE)),
+        }
+    }
+}
+
+impl FromItem for Vec<u8> {
+    fn from_item(item: &Item) -> Result<Self, Error> {
+        match item.as_bytes() {
+            Some(b) => Ok(b.to_vec()),
+            None => Err(Error::new(ERR_INVALID_TYPE)),
+        }
+    }
+}
+
+impl FromItem for String {
+    fn from_item(item: &Item) -> Result<Self, Error> {
+        match item.as_text() {
+            Some(s) => Ok(s.into()),
+            None => Err(Error::new(ERR_INVALID_TYPE)),
+        }
+    }
+}
+
+/// `null` (and `undefined`) is decoded as `None`.
+impl<T: FromItem> FromItem for Option<T> {
+    fn from_item(item: &Item) -> Result<Self, Error> {
+        match item.is_null() {
+            true => Ok(None),
+            false => T::from_item(item).map(Some),
+        }
+    }
+}
+
+const BREAK: u8 = 0xff;
+
+struct Parser<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        if n > self.data.len() - self.pos {
+            return Err(Error::new(ERR_TRUNCATED));
+        }
+        let sp = self.pos;
+        self.pos += n;
+        Ok(&self.data[sp..self.pos])
+    }
+
+    fn peek(&self) -> Result<u8, Error> {
+        match self.data.get(self.pos) {
+            Some(b) => Ok(*b),
+            None => Err(Error::new(ERR_TRUNCATED)),
+        }
+    }
+
+    /// reads the argument of the item, `None` means indefinite length.
+    fn argument(&mut self, info: u8) -> Result<Option<u64>, Error> {
+        match info {
+            0..24 => Ok(Some(info as u64)),
+            24..28 => {
+                let b = self.take(1 << (info - 24))?;
+                Ok(Some(b.iter().fold(0, |acc, b| (acc << 8) | *b as u64)))
+            }
+            31 => Ok(None),
+            _ => Err(Error::new(ERR_MALFORMED)),
+        }
+    }
+
+    /// reads definite length argument (length of string, array or map).
+    fn length(&mut self, arg: u64) -> Result<usize, Error> {
+        // every item takes at least one byte so length can't exceed the input
+        match usize::try_from(arg) {
+            Ok(n) if n <= self.data.len() - self.pos => Ok(n),
+            _ => Err(Error::new(ERR_TRUNCATED)),
+        }
+    }
+
+    fn string(&mut self, major: u8, arg: Option<u64>) -> Result<Vec<u8>, Error> {
+        if let Some(n) = arg {
+            let n = self.length(n)?;
+            return Ok(self.take(n)?.to_vec());
+        }
+        // indefinite length string is a sequence of definite length strings
+        let mut buf = Vec::new();
+        while self.peek()? != BREAK {
+            let ib = self.take(1)?[0];
+            if ib >> 5 != major {
+                return Err(Error::new(ERR_MALFORMED));
+            }
+            let n = match self.argument(ib & 0x1f)? {
+                Some(n) => self.length(n)?,
+                None => return Err(Error::new(ERR_MALFORMED)),
+            };
+            buf.extend_from_slice(self.take(n)?);
+        }
+        self.pos += 1;
+        Ok(buf)
+    }
+
+    /// returns true when there is more items in the array/map, `remaining`
+    /// is `None` for indefinite length container.
+    fn has_next(&mut self, remaining: &mut Option<usize>) -> Result<bool, Error> {
+        match remaining {
+            Some(0) => Ok(false),
+            Some(n) => {
+                *n -= 1;
+                Ok(true)
+            }
+            None if self.peek()? == BREAK => {
+                self.pos += 1;
+                Ok(false)
+            }
+            None => Ok(true),
+        }
+    }
+
+    fn item(&mut self, depth: usize) -> Result<Item, Error> {
+        if depth > MAX_DEPTH {
+            return Err(Error::new(ERR_TOO_DEEP));
+        }
+        let ib = self.take(1)?[0];
+        let (major, info) = (ib >> 5, ib & 0x1f);
+        let arg = self.argument(info)?;
+        match (major, arg) {
+            (0, Some(n)) => Ok(Item::Uint(n)),
+            (1, Some(n)) => Ok(Item::NegInt(n)),
+            (2, _) => Ok(Item::Bytes(self.string(major, arg)?)),
+            (3, _) => match String::from_utf8(self.string(major, arg)?) {
+                Ok(s) => Ok(Item::Text(s)),
+                Err(_) => Err(Error::new(ERR_INVALID_UTF8)),
+            },
+            (4, _) => {
+                let mut remaining = arg.map(|n| self.length(n)).transpose()?;
+                let mut items = Vec::with_capacity(remaining.unwrap_or(0));
+                while self.has_next(&mut remaining)? {
+                    items.push(self.item(depth + 1)?);
+                }
+                Ok(Item::Array(items))
+            }
+            (5, _) => {
+                let mut remaining = arg.map(|n| self.length(n)).transpose()?;
+                let mut items = Vec::with_capacity(remaining.unwrap_or(0) / 2);
+                while self.has_next(&mut remaining)? {
+                    let key = self.item(depth + 1)?;
+                    items.push((key, self.item(depth + 1)?));
+                }
+                Ok(Item::Map(items))
+            }
+            (6, Some(tag)) => Ok(Item::Tag(tag, Box::new(self.item(depth + 1)?))),
+            (7, Some(n)) => match info {
+                20 => Ok(Item::Bool(false)),
+                21 => Ok(Item::Bool(true)),
+                22 => Ok(Item::Null),
+                23 => Ok(Item::Undefined),
+                0..20 | 24 => Ok(Item::Simple(n as u8)),
+                25 => Ok(Item::Float(f16_to_f64(n as u16))),
+                26 => Ok(Item::Float(f32::from_bits(n as u32) as f64)),
+                _ => Ok(Item::Float(f64::from_bits(n))),
+            },
+            // indefinite length int/tag or unexpected "break"
+            _ => Err(Error::new(ERR_MALFORMED)),
+        }
+    }
+}
+
+fn f16_to_f64(h: u16) -> f64 {
+    let exp = ((h >> 10) & 0x1f) as i64;
+    let mant = (h & 0x3ff) as f64;
+    let v = match exp {
+        0 => mant / (1 << 24) as f64,
+        31 if mant == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        // (1024 + mant) * 2^(exp - 25)
+        _ => (1024.0 + mant) * f64::from_bits(((exp - 25 + 1023) as u64) << 52),
+    };
+    if h & 0x8000 != 0 { -v } else { v }
+}
+
+/**
+CBOR encoder, integers and lengths are encoded using the shortest form.
+
+Arrays and maps are encoded by writing the header with [`array`](Encoder::array)
+or [`map`](Encoder::map) followed by the items, ie
+```
+use alphabill::cbor::Encoder;
+
+// [h'01', {"a": -2}]
+let data = Encoder::new().array(2).bytes(&[1]).map(1).text("a").int(-2).finish();
+assert_eq!(data, [0x82, 0x41, 0x01, 0xa1, 0x61, 0x61, 0x21]);
+```
+*/
+#[derive(Default)]
+pub struct Encoder {
+    buf: Vec<u8>,
+}
+
+impl Encoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn uint(mut self, n: u64) -> Self {
+        self.head(0, n);
+        self
+    }
+
+    pub fn int(mut self, n: i64) -> Self {
+        match n {
+            0.. => self.head(0, n as u64),
+            _ => self.head(1, !n as u64),
+        }
+        self
+    }
+
+    pub fn bytes(mut self, b: &[u8]) -> Self {
+        self.head(2, b.len() as u64);
+        self.buf.extend_from_slice(b);
+        self
+    }
+
+    pub fn text(mut self, s: &str) -> Self {
+        self.head(3, s.len() as u64);
+        self.buf.extend_from_slice(s.as_bytes());
+        self
+    }
+
+    /// writes array header, must be followed by `len` items.
+    pub fn array(mut self, len: usize) -> Self {
+        self.head(4, len as u64);
+        self
+    }
+
+    /// writes map header, must be followed by `len` key-value pairs.
+    pub fn map(mut self, len: usize) -> Self {
+        self.head(5, len as u64);
+        self
+    }
+
+    /// writes tag number, must be followed by the tagged item.
+    pub fn tag(mut self, tag: u64) -> Self {
+        self.head(6, tag);
+        self
+    }
+
+    pub fn bool(mut self, v: bool) -> Self {
+        self.buf.push(if v { 0xf5 } else { 0xf4 });
+        self
+    }
+
+    pub fn null(mut self) -> Self {
+        self.buf.push(0xf6);
+        self
+    }
+
+    pub fn float(mut self, v: f64) -> Self {
+        self.buf.push(0xfb);
+        self.buf.extend_from_slice(&v.to_bits().to_be_bytes());
+        self
+    }
+
+    pub fn item(self, item: &Item) -> Self {
+        match item {
+            Item::Uint(n) => self.uint(*n),
+            Item::NegInt(n) => {
+                let mut e = self;
+                e.head(1, *n);
+                e
+            }
+            Item::Bytes(b) => self.bytes(b),
+            Item::Text(s) => self.text(s),
+            Item::Array(items) => items.iter().fold(self.array(items.len()), Self::item),
+            Item::Map(items) => items
+                .iter()
+                .fold(self.map(items.len()), |e, (k, v)| e.item(k).item(v)),
+            Item::Tag(tag, v) => self.tag(*tag).item(v),
+            Item::Bool(v) => self.bool(*v),
+            Item::Null => self.null(),
+            Item::Undefined => {
+                let mut e = self;
+                e.buf.push(0xf7);
+                e
+            }
+            Item::Simple(n) => {
+                let mut e = self;
+                e.head(7, *n as u64);
+                e
+            }
+            Item::Float(v) => self.float(*v),
+        }
+    }
+
+    /// returns the encoded data.
+    pub fn finish(self) -> Vec<u8> {
+        self.buf
+    }
+
+    fn head(&mut self, major: u8, arg: u64) {
+        let mt = major << 5;
+        match arg {
+            0..24 => self.buf.push(mt | arg as u8),
+            24..0x100 => self.buf.extend_from_slice(&[mt | 24, arg as u8]),
+            0x100..0x10000 => {
+                self.buf.push(mt | 25);
+                self.buf.extend_from_slice(&(arg as u16).to_be_bytes());
+            }
+            0x10000..0x100000000 => {
+                self.buf.push(mt | 26);
+                self.buf.extend_from_slice(&(arg as u32).to_be_bytes());
+            }
+            _ => {
+                self.buf.push(mt | 27);
+                self.buf.extend_from_slice(&arg.to_be_bytes());
+            }
+        }
+    }
 }
 
+#[cfg(not(feature = "mock-host"))]
 #[link(wasm_import_module = "cbor")]
 unsafe extern "C" {
     #[link_name = "parse"]
@@ -68,3 +579,236 @@ unsafe extern "C" {
     #[link_name = "chunks"]
     fn _chunks(handle: ABHandle) -> u64;
 }
+
+#[cfg(feature = "mock-host")]
+use crate::host::mock::cbor::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn text(s: &str) -> Item {
+        Item::Text(s.into())
+    }
+
+    #[test]
+    fn decode_rfc_examples() {
+        // examples from RFC 8949 Appendix A
+        let cases: &[(&[u8], Item)] = &[
+            (&[0x00], Item::Uint(0)),
+            (&[0x17], Item::Uint(23)),
+            (&[0x18, 0x18], Item::Uint(24)),
+            (&[0x19, 0x03, 0xe8], Item::Uint(1000)),
+            (&[0x1a, 0x00, 0x0f, 0x42, 0x40], Item::Uint(1000000)),
+            (
+                &[0x1b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+                Item::Uint(u64::MAX),
+            ),
+            (&[0x20], Item::NegInt(0)),
+            (&[0x38, 0x63], Item::NegInt(99)),
+            (&[0xf9, 0x3c, 0x00], Item::Float(1.0)),
+            (&[0xf9, 0xc4, 0x00], Item::Float(-4.0)),
+            (&[0xf9, 0x00, 0x01], Item::Float(5.960464477539063e-8)),
+            (&[0xf9, 0x7c, 0x00], Item::Float(f64::INFINITY)),
+            (&[0xfa, 0x47, 0xc3, 0x50, 0x00], Item::Float(100000.0)),
+            (
+                &[0xfb, 0x3f, 0xf1, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9a],
+                Item::Float(1.1),
+            ),
+            (&[0xf4], Item::Bool(false)),
+            (&[0xf5], Item::Bool(true)),
+            (&[0xf6], Item::Null),
+            (&[0xf7], Item::Undefined),
+            (&[0xf0], Item::Simple(16)),
+            (&[0xf8, 0xff], Item::Simple(255)),
+            (
+                &[0xc1, 0x1a, 0x51, 0x4b, 0x67, 0xb0],
+                Item::Tag(1, Box::new(Item::Uint(1363896240))),
+            ),
+            (&[0x40], Item::Bytes(vec![])),
+            (
+                &[0x44, 0x01, 0x02, 0x03, 0x04],
+                Item::Bytes(vec![1, 2, 3, 4]),
+            ),
+            (&[0x60], text("")),
+            (&[0x62, 0xc3, 0xbc], text("\u{00fc}")),
+            (&[0x80], Item::Array(vec![])),
+            (
+                &[0x83, 0x01, 0x82, 0x02, 0x03, 0x82, 0x04, 0x05],
+                Item::Array(vec![
+                    Item::Uint(1),
+                    Item::Array(vec![Item::Uint(2), Item::Uint(3)]),
+                    Item::Array(vec![Item::Uint(4), Item::Uint(5)]),
+                ]),
+            ),
+            (
+                &[0xa2, 0x61, 0x61, 0x01, 0x61, 0x62, 0x82, 0x02, 0x03],
+                Item::Map(vec![
+                    (text("a"), Item::Uint(1)),
+                    (text("b"), Item::Array(vec![Item::Uint(2), Item::Uint(3)])),
+                ]),
+            ),
+            // indefinite length
+            (
+                &[0x5f, 0x42, 0x01, 0x02, 0x43, 0x03, 0x04, 0x05, 0xff],
+                Item::Bytes(vec![1, 2, 3, 4, 5]),
+            ),
+            (
+                &[
+                    0x7f, 0x65, 0x73, 0x74, 0x72, 0x65, 0x61, 0x64, 0x6d, 0x69, 0x6e, 0x67, 0xff,
+                ],
+                text("streaming"),
+            ),
+            (&[0x9f, 0xff], Item::Array(vec![])),
+            (
+                &[0x9f, 0x01, 0x82, 0x02, 0x03, 0x9f, 0x04, 0x05, 0xff, 0xff],
+                Item::Array(vec![
+                    Item::Uint(1),
+                    Item::Array(vec![Item::Uint(2), Item::Uint(3)]),
+                    Item::Array(vec![Item::Uint(4), Item::Uint(5)]),
+                ]),
+            ),
+            (
+                &[
+                    0xbf, 0x61, 0x61, 0x01, 0x61, 0x62, 0x9f, 0x02, 0x03, 0xff, 0xff,
+                ],
+                Item::Map(vec![
+                    (text("a"), Item::Uint(1)),
+                    (text("b"), Item::Array(vec![Item::Uint(2), Item::Uint(3)])),
+                ]),
+            ),
+        ];
+        for (data, item) in cases {
+            assert_eq!(decode(data).unwrap(), *item, "decoding {data:x?}");
+        }
+        assert!(matches!(decode(&[0xf9, 0x7e, 0x00]), Ok(Item::Float(v)) if v.is_nan()));
+    }
+
+    #[test]
+    fn decode_errors() {
+        let cases: &[(&[u8], u8)] = &[
+            (&[], ERR_TRUNCATED),
+            (&[0x18], ERR_TRUNCATED),
+            (&[0x44, 0x01, 0x02], ERR_TRUNCATED),
+            (
+                &[0x5b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+                ERR_TRUNCATED,
+            ),
+            (&[0x82, 0x01], ERR_TRUNCATED),
+            (&[0x9f, 0x01], ERR_TRUNCATED),
+            (&[0x62, 0xc3, 0x28], ERR_INVALID_UTF8),
+            // reserved additional info values
+            (&[0x1c], ERR_MALFORMED),
+            (&[0xfe], ERR_MALFORMED),
+            // indefinite length integer and unexpected break
+            (&[0x1f], ERR_MALFORMED),
+            (&[0xff], ERR_MALFORMED),
+            // chunk of indefinite length byte string is text
+            (&[0x5f, 0x61, 0x61, 0xff], ERR_MALFORMED),
+            // trailing data
+            (&[0x01, 0x02], ERR_MALFORMED),
+        ];
+        for (data, code) in cases {
+            assert_eq!(
+                decode(data).unwrap_err().code(),
+                *code as u64,
+                "decoding {data:x?}"
+            );
+        }
+
+        let mut deep = vec![0x81; MAX_DEPTH];
+        deep.push(0x01);
+        assert!(decode(&deep).is_ok());
+        deep.insert(0, 0x81);
+        assert_eq!(decode(&deep).unwrap_err().code(), ERR_TOO_DEEP as u64);
+    }
+
+    #[test]
+    fn encode() {
+        assert_eq!(Encoder::new().uint(0).finish(), [0x00]);
+        assert_eq!(Encoder::new().uint(24).finish(), [0x18, 0x18]);
+        assert_eq!(Encoder::new().uint(1000).finish(), [0x19, 0x03, 0xe8]);
+        assert_eq!(
+            Encoder::new().uint(1000000).finish(),
+            [0x1a, 0x00, 0x0f, 0x42, 0x40]
+        );
+        assert_eq!(
+            Encoder::new().uint(1000000000000).finish(),
+            [0x1b, 0x00, 0x00, 0x00, 0xe8, 0xd4, 0xa5, 0x10, 0x00]
+        );
+        assert_eq!(Encoder::new().int(-1).finish(), [0x20]);
+        assert_eq!(Encoder::new().int(-1000).finish(), [0x39, 0x03, 0xe7]);
+        assert_eq!(
+            Encoder::new().int(i64::MIN).finish(),
+            [0x3b, 0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]
+        );
+        assert_eq!(Encoder::new().text("IETF").finish(), b"\x64IETF");
+        assert_eq!(
+            Encoder::new().tag(1).uint(1363896240).finish(),
+            [0xc1, 0x1a, 0x51, 0x4b, 0x67, 0xb0]
+        );
+        assert_eq!(
+            Encoder::new()
+                .array(3)
+                .bool(false)
+                .bool(true)
+                .null()
+                .finish(),
+            [0x83, 0xf4, 0xf5, 0xf6]
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let item = Item::Array(vec![
+            Item::Uint(u64::MAX),
+            Item::NegInt(500),
+            Item::Bytes(vec![0; 300]),
+            text("foo"),
+            Item::Map(vec![
+                (Item::Uint(1), Item::Null),
+                (text("k"), Item::Undefined),
+            ]),
+            Item::Tag(1001, Box::new(Item::Simple(200))),
+            Item::Float(-1.5),
+        ]);
+        assert_eq!(decode(&item.encode()).unwrap(), item);
+    }
+
+    #[cfg(feature = "mock-host")]
+    #[test]
+    fn decode_config() {
+        use crate::{evaluation_ctx::HANDLE_CONFIG, host::mock::MockHost};
+
+        // [100, null, [_ h'01']]
+        let cfg = [0x83, 0x18, 0x64, 0xf6, 0x9f, 0x41, 0x01, 0xff];
+        MockHost::new().config(&cfg).install();
+        assert_eq!(
+            decode_var(HANDLE_CONFIG).unwrap(),
+            Item::Array(vec![
+                Item::Uint(100),
+                Item::Null,
+                Item::Array(vec![Item::Bytes(vec![1])]),
+            ])
+        );
+        let unknown: Handle<Bytes> = Handle::from_raw(99);
+        assert_eq!(
+            decode_var(unknown).unwrap_err().code(),
+            ERR_TRUNCATED as u64
+        );
+    }
+
+    #[cfg(feature = "mock-host")]
+    #[test]
+    fn parse_handle() {
+        use crate::{evaluation_ctx::HANDLE_CONFIG, host::mock::MockHost};
+
+        MockHost::new().config(&[0x82, 0x01, 0x02]).install();
+        assert!(parse(HANDLE_CONFIG).is_ok());
+        // host returns no handles for unknown variable
+        let unknown: Handle<Bytes> = Handle::from_raw(99);
+        assert_eq!(parse(unknown).unwrap_err().code(), ERR_MISSING_ITEM as u64);
+        assert!(parse_array(unknown).is_empty());
+    }
+}
@@ -6,67 +6,74 @@ extern crate alloc;
 use alloc::{string::String, vec::Vec};
 
 use crate::{
+    cbor::ERR_TOO_DEEP,
     error::{self, Error},
-    evaluation_ctx::{self, ABHandle},
-    memory,
 };
 
-#[derive(Copy, Clone)]
+/// error code returned when the data ends before the value is fully decoded.
+pub const ERR_TRUNCATED: u8 = 2;
+/// error code returned when string value is not valid UTF-8.
+pub const ERR_INVALID_UTF8: u8 = 3;
+/// error code returned when the type ID of the value is not known to the decoder.
+pub const ERR_UNKNOWN_TYPE: u8 = 4;
+
+/// maximum nesting depth of arrays the decoder accepts, deeper data is rejected
+/// with [`ERR_TOO_DEEP`](crate::cbor::ERR_TOO_DEEP).
+pub const MAX_DEPTH: usize = 16;
+
+/**
+Decoder for the data serialized by the host.
+
+The input is usually the buffer returned by the host, ie
+```no_run
+use alphabill::{decoder::Decoder, evaluation_ctx};
+
+let buf = evaluation_ctx::serialize_obj(evaluation_ctx::HANDLE_TX_ORDER, 1);
+let mut d = Decoder::new(&buf);
+```
+
+Methods without `try_` prefix panic when the data is truncated or malformed,
+the `try_` methods return [`Error`] instead (see `ERR_*` constants for codes).
+*/
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Decoder<'a> {
     data: &'a [u8],
     pos: usize,
 }
 
-impl<'a> Decoder<'_> {
+impl<'a> Decoder<'a> {
     /// create new decoder with given byte slice as input data.
     pub fn new(data: &'a [u8]) -> Decoder<'a> {
         Decoder { data, pos: 0 }
     }
 
-    pub fn from_handle(h: ABHandle) -> Decoder<'a> {
-        let ptr = evaluation_ctx::serialize_obj(h, 0);
-        let data = memory::byte_array(ptr);
-        Decoder::new(data)
-    }
-
     /// read u32 value from data stream.
     pub fn uint32(&mut self) -> u32 {
-        let sp = self.pos;
-        self.pos += 4;
-        u32::from_le_bytes(self.data[sp..self.pos].try_into().unwrap())
+        self.try_uint32().unwrap()
     }
 
     /// read u64 value from data stream.
     pub fn uint64(&mut self) -> u64 {
-        let sp = self.pos;
-        self.pos += 8;
-        u64::from_le_bytes(self.data[sp..self.pos].try_into().unwrap())
+        self.try_uint64().unwrap()
     }
 
     /// read string value from data stream.
     pub fn string(&mut self) -> String {
-        let len = self.uint32();
-        if len == 0 {
-            return String::new();
-        }
-        let sp = self.pos;
-        self.pos += len as usize;
-        String::from_utf8(self.data[sp..self.pos].to_vec()).unwrap()
+        self.try_string().unwrap()
     }
 
     pub fn bytes(&mut self) -> Vec<u8> {
-        let len = self.uint32();
-        if len == 0 {
-            return Vec::new();
-        }
-        let sp = self.pos;
-        self.pos += len as usize;
-        let mut b = Vec::new();
-        b.extend_from_slice(&self.data[sp..self.pos]);
-        b
+        self.try_bytes().unwrap()
     }
 
     pub fn value(&mut self) -> Value {
+        self.value_at(0)
+    }
+
+    fn value_at(&mut self, depth: usize) -> Value {
+        if depth > MAX_DEPTH {
+            return Value::Error(ERR_TOO_DEEP);
+        }
         let type_id = self.data[self.pos];
         self.pos += 1;
         match type_id {
@@ -78,7 +85,7 @@ impl<'a> Decoder<'_> {
                 let cnt = self.uint32();
                 let mut a = Vec::<Value>::with_capacity(cnt as usize);
                 for _ in 0..cnt {
-                    let v = self.value();
+                    let v = self.value_at(depth + 1);
                     a.push(v);
                 }
                 Value::Array(a)
@@ -87,6 +94,136 @@ impl<'a> Decoder<'_> {
         }
     }
 
+    /// read u32 value from data stream.
+    pub fn try_uint32(&mut self) -> Result<u32, Error> {
+        let b = self.take(4)?;
+        Ok(u32::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    /// read u64 value from data stream.
+    pub fn try_uint64(&mut self) -> Result<u64, Error> {
+        let b = self.take(8)?;
+        Ok(u64::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    /// read string value from data stream.
+    pub fn try_string(&mut self) -> Result<String, Error> {
+        let b = self.try_slice()?;
+        match core::str::from_utf8(b) {
+            Ok(s) => Ok(s.into()),
+            Err(_) => Err(Error::new(ERR_INVALID_UTF8)),
+        }
+    }
+
+    /// read byte slice value from data stream.
+    pub fn try_bytes(&mut self) -> Result<Vec<u8>, Error> {
+        Ok(self.try_slice()?.to_vec())
+    }
+
+    /// read type ID prefixed value from data stream.
+    pub fn try_value(&mut self) -> Result<Value, Error> {
+        self.try_value_at(0)
+    }
+
+    fn try_value_at(&mut self, depth: usize) -> Result<Value, Error> {
+        if depth > MAX_DEPTH {
+            return Err(Error::new(ERR_TOO_DEEP));
+        }
+        match self.take(1)?[0] {
+            1 => Ok(Value::Bytes(self.try_bytes()?)),
+            2 => Ok(Value::U64(self.try_uint64()?)),
+            3 => Ok(Value::U32(self.try_uint32()?)),
+            4 => Ok(Value::String(self.try_string()?)),
+            5 => {
+                let cnt = self.try_uint32()? as usize;
+                // every item takes at least one byte so do not trust the
+                // count to be sane when allocating
+                let mut a = Vec::<Value>::with_capacity(cnt.min(self.remaining()));
+                for _ in 0..cnt {
+                    a.push(self.try_value_at(depth + 1)?);
+                }
+                Ok(Value::Array(a))
+            }
+            _ => Err(Error::new(ERR_UNKNOWN_TYPE)),
+        }
+    }
+
+    /// read byte slice value from data stream without copying it.
+    pub fn try_bytes_ref(&mut self) -> Result<&'a [u8], Error> {
+        self.try_slice()
+    }
+
+    /// read string value from data stream without copying it.
+    pub fn try_str_ref(&mut self) -> Result<&'a str, Error> {
+        core::str::from_utf8(self.try_slice()?).or(Err(Error::new(ERR_INVALID_UTF8)))
+    }
+
+    /**
+    Read type ID prefixed value from data stream without copying it.
+
+    Arrays are not decoded but [`ArrayIter`] is returned which decodes the
+    items on demand.
+    */
+    pub fn try_value_ref(&mut self) -> Result<ValueRef<'a>, Error> {
+        match self.take(1)?[0] {
+            1 => Ok(ValueRef::Bytes(self.try_bytes_ref()?)),
+            2 => Ok(ValueRef::U64(self.try_uint64()?)),
+            3 => Ok(ValueRef::U32(self.try_uint32()?)),
+            4 => Ok(ValueRef::Str(self.try_str_ref()?)),
+            5 => {
+                let count = self.try_uint32()?;
+                let sp = self.pos;
+                for _ in 0..count {
+                    self.try_skip_value(1)?;
+                }
+                Ok(ValueRef::Array(ArrayIter {
+                    dec: Decoder::new(&self.data[sp..self.pos]),
+                    count,
+                }))
+            }
+            _ => Err(Error::new(ERR_UNKNOWN_TYPE)),
+        }
+    }
+
+    /// move position past the type ID prefixed value.
+    fn try_skip_value(&mut self, depth: usize) -> Result<(), Error> {
+        if depth > MAX_DEPTH {
+            return Err(Error::new(ERR_TOO_DEEP));
+        }
+        match self.take(1)?[0] {
+            1 | 4 => self.try_slice().map(|_| ()),
+            2 => self.take(8).map(|_| ()),
+            3 => self.take(4).map(|_| ()),
+            5 => {
+                for _ in 0..self.try_uint32()? {
+                    self.try_skip_value(depth + 1)?;
+                }
+                Ok(())
+            }
+            _ => Err(Error::new(ERR_UNKNOWN_TYPE)),
+        }
+    }
+
+    /// read length prefixed byte slice from data stream.
+    fn try_slice(&mut self) -> Result<&'a [u8], Error> {
+        let len = self.try_uint32()?;
+        self.take(len as usize)
+    }
+
+    /// returns next `n` bytes from data stream.
+    fn take(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        if n > self.remaining() {
+            return Err(Error::new(ERR_TRUNCATED));
+        }
+        let sp = self.pos;
+        self.pos += n;
+        Ok(&self.data[sp..self.pos])
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len().saturating_sub(self.pos)
+    }
+
     /// read current position in the data as "tag" value
     fn tag(&mut self) -> u8 {
         let tag = self.data[self.pos];
@@ -119,6 +256,126 @@ impl<'a> Iterator for TagValueIter<'a> {
     }
 }
 
+/**
+Iterator over the tag-value pairs of the data stream which, unlike [`TagValueIter`],
+doesn't panic on malformed data but returns [`Error`] as the item (and ends
+the iteration after that).
+*/
+pub struct TryTagValueIter<'a> {
+    dec: Decoder<'a>,
+}
+
+impl<'a> TryTagValueIter<'a> {
+    pub fn new(data: &'a [u8]) -> TryTagValueIter<'a> {
+        TryTagValueIter {
+            dec: Decoder::new(data),
+        }
+    }
+}
+
+impl Iterator for TryTagValueIter<'_> {
+    type Item = Result<(u8, Value), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.dec.remaining() == 0 {
+            return None;
+        }
+        let tag = self.dec.tag();
+        let r = self.dec.try_value().map(|v| (tag, v));
+        if r.is_err() {
+            // do not attempt to decode anything after invalid field
+            self.dec.pos = self.dec.data.len();
+        }
+        Some(r)
+    }
+}
+
+/**
+Iterator over the tag-value pairs of the data stream which returns borrowed
+values, see [`Decoder::try_value_ref`]. Iteration ends after the first error.
+
+Allows to inspect the data without heap allocations, ie
+```
+use alphabill::decoder::{TagValueRefIter, ValueRef};
+
+// tag 4: Bytes("early-bird")
+let data = [4, 1, 10, 0, 0, 0, b'e', b'a', b'r', b'l', b'y', b'-', b'b', b'i', b'r', b'd'];
+let early_bird = TagValueRefIter::new(&data)
+    .any(|fld| matches!(fld, Ok((4, ValueRef::Bytes(b"early-bird")))));
+assert!(early_bird);
+```
+*/
+pub struct TagValueRefIter<'a> {
+    dec: Decoder<'a>,
+}
+
+impl<'a> TagValueRefIter<'a> {
+    pub fn new(data: &'a [u8]) -> TagValueRefIter<'a> {
+        TagValueRefIter {
+            dec: Decoder::new(data),
+        }
+    }
+}
+
+impl<'a> Iterator for TagValueRefIter<'a> {
+    type Item = Result<(u8, ValueRef<'a>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.dec.remaining() == 0 {
+            return None;
+        }
+        let tag = self.dec.tag();
+        let r = self.dec.try_value_ref().map(|v| (tag, v));
+        if r.is_err() {
+            self.dec.pos = self.dec.data.len();
+        }
+        Some(r)
+    }
+}
+
+/**
+Value borrowing the data from the decoder's input, see [`Decoder::try_value_ref`].
+*/
+#[derive(Debug, PartialEq, Clone)]
+pub enum ValueRef<'a> {
+    U64(u64),
+    U32(u32),
+    Bytes(&'a [u8]),
+    Str(&'a str),
+    Array(ArrayIter<'a>),
+}
+
+/**
+Iterator over the items of an array value, items are decoded on demand.
+*/
+#[derive(Debug, PartialEq, Clone)]
+pub struct ArrayIter<'a> {
+    dec: Decoder<'a>,
+    count: u32,
+}
+
+impl ArrayIter<'_> {
+    /// number of items in the array.
+    pub fn item_count(&self) -> u32 {
+        self.count
+    }
+}
+
+impl<'a> Iterator for ArrayIter<'a> {
+    type Item = Result<ValueRef<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.dec.pos >= self.dec.data.len() {
+            return None;
+        }
+        Some(self.dec.try_value_ref())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.count as usize))
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum Value {
     U64(u64),
@@ -129,63 +386,89 @@ pub enum Value {
     Error(u8), // error code
 }
 
-impl TryFrom<Value> for u32 {
+impl<'a> TryFrom<ValueRef<'a>> for &'a [u8] {
     type Error = error::Error;
 
-    fn try_from(v: Value) -> Result<Self, Self::Error> {
+    fn try_from(v: ValueRef<'a>) -> Result<Self, Self::Error> {
         match v {
-            Value::U32(n) => Ok(n),
+            ValueRef::Bytes(b) => Ok(b),
+            ValueRef::Str(s) => Ok(s.as_bytes()),
             _ => Err(Error::new(1)),
         }
     }
 }
 
-impl TryFrom<Value> for u64 {
+impl<'a> TryFrom<ValueRef<'a>> for &'a str {
     type Error = error::Error;
 
-    fn try_from(v: Value) -> Result<Self, Self::Error> {
+    fn try_from(v: ValueRef<'a>) -> Result<Self, Self::Error> {
         match v {
-            Value::U32(n) => Ok(n.into()),
-            Value::U64(n) => Ok(n),
+            ValueRef::Str(s) => Ok(s),
+            ValueRef::Bytes(b) => core::str::from_utf8(b).or(Err(Error::new(ERR_INVALID_UTF8))),
+            _ => Err(Error::new(1)),
+        }
+    }
+}
+
+impl TryFrom<ValueRef<'_>> for u32 {
+    type Error = error::Error;
+
+    fn try_from(v: ValueRef<'_>) -> Result<Self, Self::Error> {
+        match v {
+            ValueRef::U32(n) => Ok(n),
+            _ => Err(Error::new(1)),
+        }
+    }
+}
+
+impl TryFrom<ValueRef<'_>> for u64 {
+    type Error = error::Error;
+
+    fn try_from(v: ValueRef<'_>) -> Result<Self, Self::Error> {
+        match v {
+            ValueRef::U32(n) => Ok(n.into()),
+            ValueRef::U64(n) => Ok(n),
             _ => Err(Error::new(1)),
         }
     }
 }
-impl TryFrom<Value> for Option<u64> {
+
+impl TryFrom<Value> for u32 {
     type Error = error::Error;
 
     fn try_from(v: Value) -> Result<Self, Self::Error> {
-        match <Value as TryInto<u64>>::try_into(v) {
-            Ok(n) => Ok(Some(n)),
-            Err(err) => Err(err.chain(1)),
+        match v {
+            Value::U32(n) => Ok(n),
+            _ => Err(Error::new(1)),
         }
     }
 }
 
-impl TryFrom<Value> for String {
+impl TryFrom<Value> for u64 {
     type Error = error::Error;
 
     fn try_from(v: Value) -> Result<Self, Self::Error> {
         match v {
-            Value::String(s) => Ok(s),
-            Value::Bytes(b) => Ok(unsafe { String::from_utf8_unchecked(b.to_vec()) }),
+            Value::U32(n) => Ok(n.into()),
+            Value::U64(n) => Ok(n),
             _ => Err(Error::new(1)),
         }
     }
 }
-impl TryFrom<Value> for Option<String> {
+
+impl TryFrom<Value> for String {
     type Error = error::Error;
 
     fn try_from(v: Value) -> Result<Self, Self::Error> {
         match v {
-            Value::String(s) => Ok(Some(s)),
-            Value::Bytes(b) => Ok(Some(unsafe { String::from_utf8_unchecked(b.to_vec()) })),
+            Value::String(s) => Ok(s),
+            Value::Bytes(b) => String::from_utf8(b).or(Err(Error::new(ERR_INVALID_UTF8))),
             _ => Err(Error::new(1)),
         }
     }
 }
 
-impl<'a> TryFrom<Value> for Vec<u8> {
+impl TryFrom<Value> for Vec<u8> {
     type Error = error::Error;
 
     fn try_from<'b>(v: Value) -> Result<Self, Self::Error> {
@@ -196,14 +479,205 @@ impl<'a> TryFrom<Value> for Vec<u8> {
         }
     }
 }
-impl<'a> TryFrom<Value> for Option<Vec<u8>> {
+
+/// array of byte slices (ie raw CBOR of the proofs).
+impl TryFrom<Value> for Vec<Vec<u8>> {
     type Error = error::Error;
 
-    fn try_from<'b>(v: Value) -> Result<Self, Self::Error> {
+    fn try_from(v: Value) -> Result<Self, Self::Error> {
         match v {
-            Value::String(s) => Ok(Some(s.into_bytes())),
-            Value::Bytes(b) => Ok(Some(b)),
+            Value::Array(items) => items.into_iter().map(Vec::<u8>::try_from).collect(),
             _ => Err(Error::new(1)),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn truncated() {
+        let mut dec = Decoder::new(&[1, 2, 3]);
+        assert_eq!(dec.try_uint32().unwrap_err().code(), ERR_TRUNCATED as u64);
+
+        let mut dec = Decoder::new(&[1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(dec.try_uint64().unwrap_err().code(), ERR_TRUNCATED as u64);
+
+        // length says 4 bytes but only 3 follow
+        let mut dec = Decoder::new(&[4, 0, 0, 0, 0x61, 0x62, 0x63]);
+        assert_eq!(dec.try_string().unwrap_err().code(), ERR_TRUNCATED as u64);
+        let mut dec = Decoder::new(&[4, 0, 0, 0, 1, 2, 3]);
+        assert_eq!(dec.try_bytes().unwrap_err().code(), ERR_TRUNCATED as u64);
+        let mut dec = Decoder::new(&[0xff, 0xff, 0xff, 0xff, 1, 2, 3]);
+        assert_eq!(dec.try_bytes().unwrap_err().code(), ERR_TRUNCATED as u64);
+
+        // array of 2 items but only one item is present
+        let mut dec = Decoder::new(&[5, 2, 0, 0, 0, 3, 1, 0, 0, 0]);
+        assert_eq!(dec.try_value().unwrap_err().code(), ERR_TRUNCATED as u64);
+
+        let mut dec = Decoder::new(&[]);
+        assert_eq!(dec.try_value().unwrap_err().code(), ERR_TRUNCATED as u64);
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let mut dec = Decoder::new(&[2, 0, 0, 0, 0xc3, 0x28]);
+        assert_eq!(
+            dec.try_string().unwrap_err().code(),
+            ERR_INVALID_UTF8 as u64
+        );
+
+        let mut dec = Decoder::new(&[4, 1, 0, 0, 0, 0xff]);
+        assert_eq!(dec.try_value().unwrap_err().code(), ERR_INVALID_UTF8 as u64);
+    }
+
+    #[test]
+    fn too_deep() {
+        // [[...[U32(1)]...]] nested `depth` times
+        let nested = |depth: usize| {
+            let mut data = [5, 1, 0, 0, 0].repeat(depth);
+            data.extend([3, 1, 0, 0, 0]);
+            data
+        };
+        let data = nested(MAX_DEPTH);
+        assert!(Decoder::new(&data).try_value().is_ok());
+        assert!(Decoder::new(&data).try_value_ref().is_ok());
+        assert!(!matches!(Decoder::new(&data).value(), Value::Error(_)));
+
+        let data = nested(MAX_DEPTH + 1);
+        let code = ERR_TOO_DEEP as u64;
+        assert_eq!(Decoder::new(&data).try_value().unwrap_err().code(), code);
+        assert_eq!(
+            Decoder::new(&data).try_value_ref().unwrap_err().code(),
+            code
+        );
+        let mut v = Decoder::new(&data).value();
+        while let Value::Array(mut items) = v {
+            v = items.pop().unwrap();
+        }
+        assert_eq!(v, Value::Error(ERR_TOO_DEEP));
+    }
+
+    #[test]
+    fn unknown_type() {
+        let mut dec = Decoder::new(&[9, 1, 0, 0, 0]);
+        assert_eq!(dec.try_value().unwrap_err().code(), ERR_UNKNOWN_TYPE as u64);
+    }
+
+    #[test]
+    fn try_values() {
+        let mut dec = Decoder::new(&[
+            3, 1, 2, 0, 0, 1, 2, 0, 0, 0, 0xa, 0xb, 5, 1, 0, 0, 0, 4, 1, 0, 0, 0, 0x41,
+        ]);
+        assert_eq!(dec.try_value().unwrap(), Value::U32(0x201));
+        assert_eq!(dec.try_value().unwrap(), Value::Bytes(vec![0xa, 0xb]));
+        assert_eq!(
+            dec.try_value().unwrap(),
+            Value::Array(vec![Value::String("A".into())])
+        );
+        assert_eq!(dec.try_value().unwrap_err().code(), ERR_TRUNCATED as u64);
+    }
+
+    #[test]
+    fn try_iterator() {
+        let data = [1, 3, 1, 0, 0, 0, 2, 3, 2, 0, 0, 0];
+        let items: Result<Vec<_>, _> = TryTagValueIter::new(&data).collect();
+        assert_eq!(items.unwrap(), vec![(1, Value::U32(1)), (2, Value::U32(2))]);
+
+        // second field is of unknown type, iteration must end after the error
+        let data = [1, 3, 1, 0, 0, 0, 2, 9, 0, 3, 3, 3, 0, 0, 0];
+        let mut it = TryTagValueIter::new(&data);
+        assert_eq!(it.next().unwrap().unwrap(), (1, Value::U32(1)));
+        assert_eq!(
+            it.next().unwrap().unwrap_err().code(),
+            ERR_UNKNOWN_TYPE as u64
+        );
+        assert!(it.next().is_none());
+    }
+
+    #[test]
+    fn value_ref() {
+        // Bytes(0a0b), String("AB"), U64(7), U32(1)
+        let data = [
+            1, 2, 0, 0, 0, 0xa, 0xb, 4, 2, 0, 0, 0, 0x41, 0x42, 2, 7, 0, 0, 0, 0, 0, 0, 0, 3, 1, 0,
+            0, 0,
+        ];
+        let mut dec = Decoder::new(&data);
+        assert_eq!(dec.try_value_ref().unwrap(), ValueRef::Bytes(&[0xa, 0xb]));
+        let s: &str = dec.try_value_ref().unwrap().try_into().unwrap();
+        assert_eq!(s, "AB");
+        assert_eq!(dec.try_value_ref().unwrap(), ValueRef::U64(7));
+        let n: u64 = dec.try_value_ref().unwrap().try_into().unwrap();
+        assert_eq!(n, 1);
+        assert_eq!(
+            dec.try_value_ref().unwrap_err().code(),
+            ERR_TRUNCATED as u64
+        );
+    }
+
+    #[test]
+    fn array_ref() {
+        // [U32(0x20), [Bytes(ff)], String("AB")] followed by U32(1)
+        let data = [
+            5, 3, 0, 0, 0, 3, 0x20, 0, 0, 0, 5, 1, 0, 0, 0, 1, 1, 0, 0, 0, 0xff, 4, 2, 0, 0, 0,
+            0x41, 0x42, 3, 1, 0, 0, 0,
+        ];
+        let mut dec = Decoder::new(&data);
+        let ValueRef::Array(mut items) = dec.try_value_ref().unwrap() else {
+            panic!("expected array")
+        };
+        assert_eq!(items.item_count(), 3);
+        assert_eq!(items.next().unwrap().unwrap(), ValueRef::U32(0x20));
+        let ValueRef::Array(mut sub) = items.next().unwrap().unwrap() else {
+            panic!("expected array")
+        };
+        assert_eq!(sub.next().unwrap().unwrap(), ValueRef::Bytes(&[0xff]));
+        assert!(sub.next().is_none());
+        assert_eq!(items.next().unwrap().unwrap(), ValueRef::Str("AB"));
+        assert!(items.next().is_none());
+        // decoder must have skipped the whole array
+        assert_eq!(dec.try_value_ref().unwrap(), ValueRef::U32(1));
+
+        // array of 2 items but only one item is present
+        let mut dec = Decoder::new(&[5, 2, 0, 0, 0, 3, 1, 0, 0, 0]);
+        assert_eq!(
+            dec.try_value_ref().unwrap_err().code(),
+            ERR_TRUNCATED as u64
+        );
+    }
+
+    #[test]
+    fn conversions() {
+        let v: Result<&[u8], _> = ValueRef::Str("ab").try_into();
+        assert_eq!(v.unwrap(), b"ab");
+        let v: Result<&str, _> = ValueRef::Bytes(&[0xc3, 0x28]).try_into();
+        assert_eq!(v.unwrap_err().code(), ERR_INVALID_UTF8 as u64);
+        let v: Result<u32, _> = ValueRef::U64(1).try_into();
+        assert_eq!(v.unwrap_err().code(), 1);
+
+        let v = Value::Array(vec![Value::Bytes(vec![1]), Value::String("a".into())]);
+        assert_eq!(Vec::<Vec<u8>>::try_from(v).unwrap(), [vec![1], vec![0x61]]);
+        let v = Value::Array(vec![Value::U64(1)]);
+        assert_eq!(Vec::<Vec<u8>>::try_from(v).unwrap_err().code(), 1);
+
+        let v = String::try_from(Value::Bytes(vec![0x41, 0x42]));
+        assert_eq!(v.unwrap(), "AB");
+        let v = String::try_from(Value::Bytes(vec![0xc3, 0x28]));
+        assert_eq!(v.unwrap_err().code(), ERR_INVALID_UTF8 as u64);
+    }
+
+    #[test]
+    fn tag_value_ref_iter() {
+        let data = [1, 3, 1, 0, 0, 0, 2, 1, 1, 0, 0, 0, 0xaa, 3, 9];
+        let mut it = TagValueRefIter::new(&data);
+        assert_eq!(it.next().unwrap().unwrap(), (1, ValueRef::U32(1)));
+        assert_eq!(it.next().unwrap().unwrap(), (2, ValueRef::Bytes(&[0xaa])));
+        assert_eq!(
+            it.next().unwrap().unwrap_err().code(),
+            ERR_UNKNOWN_TYPE as u64
+        );
+        assert!(it.next().is_none());
+    }
+}
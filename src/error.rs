@@ -14,7 +14,7 @@ are lost.
 
 It doesn't implement all the methods / traits Rust error is supposed to implement!
 */
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct Error(u64);
 
 impl Error {
@@ -22,6 +22,11 @@ impl Error {
         Self(code as u64)
     }
 
+    /// restores the error out of the (chained) code returned by [`code`](Error::code)
+    pub(crate) fn from_code(code: u64) -> Self {
+        Self(code)
+    }
+
     /// cain new "code" to the existing code and return it as a new Error instance
     pub fn chain(&self, code: u8) -> Self {
         if code == 0 {
@@ -55,7 +60,180 @@ assert_eq!(0x0102, bar().expect_err("huh?").code())
 ```
 */
 pub fn error_code<T>(code: u8) -> Box<dyn FnOnce(Error) -> Result<T, Error>> {
-    return Box::new(move |err: Error| Err(err.chain(code)));
+    Box::new(move |err: Error| Err(err.chain(code)))
+}
+
+/**
+Declares the error codes of a predicate.
+
+Generates `#[repr(u8)]` enum where each variant is an error code with its
+description. The documentation of the enum gets a table of the codes so it
+doesn't drift from the codes used in the code, ie
+```
+use alphabill::error::{Error, error_codes};
+
+error_codes! {
+    /// Errors of my predicate.
+    pub enum MyError {
+        Config = 0x0c => "failed to load the configuration",
+        ProofCount = 0x1c => "number of proofs doesn't match the configuration",
+    }
+}
+
+fn load() -> Result<u64, Error> { Err(Error::new(2)) }
+
+fn check() -> Result<bool, Error> {
+    let v = load().map_err(|err| MyError::Config.chain(err))?;
+    if v != 2 {
+        return Err(MyError::ProofCount.error());
+    }
+    Ok(true)
+}
+
+assert_eq!(check().unwrap_err().code(), 0x020c);
+assert_eq!(MyError::from_code(0x0c), Some(MyError::Config));
+assert_eq!(MyError::Config.description(), "failed to load the configuration");
+```
+The enum implements:
+ - `code()` and `description()` of the variant;
+ - `error()` to create new [`Error`] with the code and `chain(err)` to chain
+   the code to the existing error;
+ - `from_code(u8)` to look up the variant, ie wallet can map the lowest byte of
+   the [`Error::code`] to human readable message;
+ - `From<Enum> for Error`.
+
+When the `error-section` feature is enabled the codes are also stored in the
+`ab_errors` custom section of the WASM module for tooling. Each code is encoded
+as the code byte followed by the length of the description (`u16`, little
+endian) and UTF-8 bytes of the description.
+*/
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __error_codes {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($variant:ident = $code:literal => $desc:literal),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        ///
+        /// | code | description |
+        /// |------|-------------|
+        $(#[doc = concat!("| `", stringify!($code), "` | ", $desc, " |")])*
+        #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+        #[repr(u8)]
+        $vis enum $name {
+            $(
+                #[doc = $desc]
+                $variant = $code,
+            )*
+        }
+
+        // not every predicate needs all the helpers
+        #[allow(dead_code)]
+        impl $name {
+            /// error code of the variant
+            pub const fn code(self) -> u8 {
+                self as u8
+            }
+
+            /// human readable description of the error
+            pub const fn description(self) -> &'static str {
+                match self {
+                    $(Self::$variant => $desc,)*
+                }
+            }
+
+            /// returns the variant with given error code
+            pub const fn from_code(code: u8) -> ::core::option::Option<Self> {
+                match code {
+                    $($code => ::core::option::Option::Some(Self::$variant),)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+
+            /// creates new error with the code of the variant
+            pub fn error(self) -> $crate::error::Error {
+                $crate::error::Error::new(self.code())
+            }
+
+            /// chains the code of the variant to the error
+            pub fn chain(self, err: $crate::error::Error) -> $crate::error::Error {
+                err.chain(self.code())
+            }
+        }
+
+        impl ::core::convert::From<$name> for $crate::error::Error {
+            fn from(v: $name) -> Self {
+                v.error()
+            }
+        }
+
+        $crate::__error_section!($(($code, $desc)),*);
+    };
+}
+
+#[doc(inline)]
+pub use crate::__error_codes as error_codes;
+
+#[cfg(feature = "error-section")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __error_section {
+    ($(($code:literal, $desc:literal)),*) => {
+        #[cfg(target_arch = "wasm32")]
+        const _: () = {
+            const CODES: &[(u8, &str)] = &[$(($code, $desc)),*];
+            #[unsafe(link_section = "ab_errors")]
+            #[used]
+            static SECTION: [u8; $crate::error::section_len(CODES)] =
+                $crate::error::section(CODES);
+        };
+    };
+}
+
+#[cfg(not(feature = "error-section"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __error_section {
+    ($(($code:literal, $desc:literal)),*) => {};
+}
+
+/// size of the custom section for the error codes, used by [`error_codes`].
+#[doc(hidden)]
+pub const fn section_len(codes: &[(u8, &str)]) -> usize {
+    let mut n = 0;
+    let mut i = 0;
+    while i < codes.len() {
+        n += 3 + codes[i].1.len();
+        i += 1;
+    }
+    n
+}
+
+/// encodes the custom section for the error codes, used by [`error_codes`].
+#[doc(hidden)]
+pub const fn section<const N: usize>(codes: &[(u8, &str)]) -> [u8; N] {
+    let mut buf = [0; N];
+    let mut pos = 0;
+    let mut i = 0;
+    while i < codes.len() {
+        let desc = codes[i].1.as_bytes();
+        assert!(desc.len() <= u16::MAX as usize, "description is too long");
+        buf[pos] = codes[i].0;
+        buf[pos + 1] = desc.len() as u8;
+        buf[pos + 2] = (desc.len() >> 8) as u8;
+        pos += 3;
+        let mut k = 0;
+        while k < desc.len() {
+            buf[pos] = desc[k];
+            pos += 1;
+            k += 1;
+        }
+        i += 1;
+    }
+    buf
 }
 
 #[cfg(test)]
@@ -111,4 +289,31 @@ mod tests {
         let r = err(1, err(2, Err(Error::new(3)))).expect_err("must return Err");
         assert_eq!(0x030201, r.code())
     }
+
+    error_codes! {
+        enum Codes {
+            One = 1 => "one",
+            Big = 0xfe => "big",
+        }
+    }
+
+    #[test]
+    fn codes() {
+        assert_eq!(Codes::Big.code(), 0xfe);
+        assert_eq!(Codes::from_code(1), Some(Codes::One));
+        assert_eq!(Codes::from_code(2), None);
+        assert_eq!(Error::from(Codes::One).code(), 1);
+        assert_eq!(Codes::Big.chain(Codes::One.error()).code(), 0x01fe);
+    }
+
+    #[test]
+    fn custom_section() {
+        const CODES: &[(u8, &str)] = &[(1, "one"), (0xfe, "")];
+        const LEN: usize = section_len(CODES);
+        assert_eq!(LEN, 9);
+        assert_eq!(
+            section::<LEN>(CODES),
+            [1, 3, 0, b'o', b'n', b'e', 0xfe, 0, 0]
+        );
+    }
 }
@@ -5,17 +5,24 @@ Provides access to the evaluation context of the predicate.
 extern crate alloc;
 use alloc::vec::Vec;
 
-use crate::{error::Error, memory, txsystem::TxOrder};
-
-/// handle to host variable
+use crate::{
+    cbor::{self, FromItem},
+    decoder::Decoder,
+    error::Error,
+    handle::{self, Bytes, Config, Handle, Object, Proof, RawData},
+    memory::{self, HostBuf},
+    txsystem::TxOrder,
+};
+
+/// raw handle to host variable, see [`Handle`] for typed handle.
 pub type ABHandle = u32;
 
 /// handle of the transaction order which triggered the predicate
-pub const HANDLE_TX_ORDER: ABHandle = 1;
+pub const HANDLE_TX_ORDER: Handle<handle::TxOrder> = Handle::from_raw(1);
 /// handle of the predicate argument (extracted from the proof).
-pub const HANDLE_ARGUMENT: ABHandle = 2;
+pub const HANDLE_ARGUMENT: Handle<Proof> = Handle::from_raw(2);
 /// handle of the argument provided when predicate record was created
-pub const HANDLE_CONFIG: ABHandle = 3;
+pub const HANDLE_CONFIG: Handle<Config> = Handle::from_raw(3);
 
 /**
 Returns current round number of the host transaction system shard.
@@ -48,9 +55,8 @@ ie [`ft::tx_attributes`], [`nft::tx_attributes`] or [`money::tx_attributes`].
 [`nft::tx_attributes`]: crate::txsystem::token::nft::tx_attributes
 [`money::tx_attributes`]: crate::txsystem::money::tx_attributes
 */
-pub fn tx_attributes(txo: ABHandle, version: u8) -> Vec<u8> {
-    let addr = unsafe { _tx_attributes(txo, version) };
-    memory::load_bytes(addr)
+pub fn tx_attributes(txo: Handle<handle::TxOrder>, version: u8) -> HostBuf {
+    unsafe { HostBuf::from_raw(_tx_attributes(txo.raw(), version)) }
 }
 
 /**
@@ -60,11 +66,9 @@ Unit must belong into the same shard the node which evaluates the predicate belo
 
 Returned data can be used as input for a constructor of specific data structure.
 */
-pub fn unit_data(unit_id: &Vec<u8>, committed: bool, version: u8) -> Vec<u8> {
-    let p = unit_id.as_ptr();
-    let addr = memory::pack_pointer((p as usize) as u32, unit_id.len());
-    let dp = unsafe { _unit_data(addr, committed, version) };
-    memory::load_bytes(dp)
+pub fn unit_data(unit_id: &[u8], committed: bool, version: u8) -> HostBuf {
+    let addr = memory::pack_slice(unit_id);
+    unsafe { HostBuf::from_raw(_unit_data(addr, committed, version)) }
 }
 
 /**
@@ -79,10 +83,9 @@ Returns handle of the variable;
 If the buffer represents some complex data type the [create_obj] api can be
 used to "convert" it to "complex structure".
 */
-pub fn add_var(data: Vec<u8>) -> ABHandle {
-    let p = data.as_ptr();
-    let addr = memory::pack_pointer((p as usize) as u32, data.len());
-    unsafe { _add_var(addr) }
+pub fn add_var(data: Vec<u8>) -> Handle<Bytes> {
+    let addr = memory::pack_slice(&data);
+    Handle::from_raw(unsafe { _add_var(addr) })
 }
 
 /**
@@ -90,28 +93,69 @@ Creates object in the host execution environment.
 
 The object lives only in the context of the current program.
 # Arguments
- * `type_id` - identifier of the native Alphabill type;
+ * `T` - the native Alphabill type, see [`Object`];
  * `handle` - the native serialized representation (CBOR) of the object;
 
-Returns handle of the object;
+Returns handle of the object, zero handle when the host failed to create it;
 */
-pub fn create_obj(type_id: u16, handle: ABHandle) -> ABHandle {
+pub fn create_obj<T: Object>(handle: Handle<Bytes>) -> Handle<T> {
     // todo: there should also be tx system id param?
-    unsafe { _create_obj_h(type_id as u32, handle) }
+    Handle::from_raw(unsafe { _create_obj_h(T::TYPE_ID as u32, handle.raw()) })
 }
 
 /**
 Export object from host side into predicate's memory.
 
-Given handle of a (host side) obj the obj is serialized into memory and the address of
-it is returned. The [decoder] can then be used to parse it.
+Given handle of a (host side) obj the obj is serialized into memory and the buffer
+containing it is returned. The [decoder] can then be used to parse it.
 
 [decoder]: crate::decoder::Decoder
 */
-pub fn serialize_obj(handle: ABHandle, version: u8) -> u64 {
-    unsafe { _serialize_obj(handle, version) }
+pub fn serialize_obj<T>(handle: Handle<T>, version: u8) -> HostBuf {
+    unsafe { HostBuf::from_raw(_serialize_obj(handle.raw(), version)) }
 }
 
+/**
+Returns the raw bytes of the host variable, ie the CBOR encoded [`HANDLE_CONFIG`]
+or [`HANDLE_ARGUMENT`] (use [`cbor::decode`] to decode it).
+
+The variable is serialized with version 0 (the version `Decoder::from_handle`
+used) in which the host returns the raw bytes as byte array value.
+
+[`cbor::decode`]: crate::cbor::decode
+*/
+pub fn var_data<T: RawData>(handle: Handle<T>) -> Result<Vec<u8>, Error> {
+    let buf = serialize_obj(handle, 0);
+    Vec::try_from(Decoder::new(&buf).try_value()?)
+}
+
+/**
+Data structure which can be loaded from the CBOR encoded array in the host
+variable, ie predicate configuration or argument.
+
+Use the [`PredicateConfig`](crate::PredicateConfig) derive macro to implement it.
+*/
+pub trait PredicateConfig: FromItem {
+    fn load<T: RawData>(handle: Handle<T>) -> Result<Self, Error> {
+        Self::from_item(&cbor::decode_var(handle)?)
+    }
+}
+
+/**
+Returns the configuration of the predicate ([`HANDLE_CONFIG`]) decoded as `T`.
+*/
+pub fn config<T: PredicateConfig>() -> Result<T, Error> {
+    T::load(HANDLE_CONFIG)
+}
+
+/**
+Returns the argument of the predicate ([`HANDLE_ARGUMENT`]) decoded as `T`.
+*/
+pub fn argument<T: PredicateConfig>() -> Result<T, Error> {
+    T::load(HANDLE_ARGUMENT)
+}
+
+#[cfg(not(feature = "mock-host"))]
 #[link(wasm_import_module = "context")]
 unsafe extern "C" {
     #[link_name = "add_var"]
@@ -135,3 +179,6 @@ unsafe extern "C" {
     #[link_name = "unit_data"]
     fn _unit_data(unit_id: u64, committed: bool, version: u8) -> u64;
 }
+
+#[cfg(feature = "mock-host")]
+use crate::host::mock::context::*;
@@ -0,0 +1,180 @@
+/*!
+Typed handles of the host variables.
+
+Host APIs refer to the objects living in the host execution environment by
+[`ABHandle`] (`u32`), to catch mix-ups (ie passing proof where PubKey hash is
+expected) at compile time the SDK wraps it into [`Handle<T>`] where `T` is a
+marker type describing the content of the variable, ie
+```compile_fail
+use alphabill::{api::signed_by_pkh, evaluation_ctx::{HANDLE_ARGUMENT, HANDLE_TX_ORDER}};
+
+// argument is the proof, not the PubKey hash
+signed_by_pkh(HANDLE_TX_ORDER, HANDLE_ARGUMENT, HANDLE_ARGUMENT);
+```
+
+Functions which create variables out of raw data (ie [`cbor::parse_array`] or
+[`evaluation_ctx::add_var`]) return [`Bytes`] handles, the caller converts them
+into the type the content is to be used as with the named conversions, ie
+```no_run
+use alphabill::{
+    api::signed_by_pkh,
+    cbor,
+    evaluation_ctx::{HANDLE_ARGUMENT, HANDLE_CONFIG, HANDLE_TX_ORDER},
+};
+
+let pkh = cbor::parse_array(HANDLE_CONFIG)[0].pub_key_hash();
+let proof = cbor::parse_array(HANDLE_ARGUMENT)[0].proof();
+signed_by_pkh(HANDLE_TX_ORDER, pkh, proof);
+```
+but the content can't be used as some other type implicitly, ie
+```compile_fail
+use alphabill::{cbor, evaluation_ctx::HANDLE_CONFIG, handle::{Handle, PubKeyHash}};
+
+let pkh: Handle<PubKeyHash> = cbor::parse_array(HANDLE_CONFIG)[0];
+```
+Native Alphabill objects are created with the type ID of the [`Object`] marker,
+see [`evaluation_ctx::create_obj`].
+
+[`ABHandle`]: crate::evaluation_ctx::ABHandle
+[`cbor::parse_array`]: crate::cbor::parse_array
+[`evaluation_ctx::add_var`]: crate::evaluation_ctx::add_var
+[`evaluation_ctx::create_obj`]: crate::evaluation_ctx::create_obj
+*/
+
+use core::{fmt, marker::PhantomData};
+
+use crate::{evaluation_ctx::ABHandle, txsystem};
+
+/// Handle of a host variable containing value described by the marker type `T`.
+#[repr(transparent)]
+pub struct Handle<T> {
+    raw: ABHandle,
+    _t: PhantomData<fn() -> T>,
+}
+
+impl<T> Handle<T> {
+    /// Wraps the raw handle returned by the host.
+    pub const fn from_raw(raw: ABHandle) -> Self {
+        Self {
+            raw,
+            _t: PhantomData,
+        }
+    }
+
+    /// Returns the raw handle to be sent to the host.
+    pub const fn raw(self) -> ABHandle {
+        self.raw
+    }
+}
+
+impl Handle<Bytes> {
+    /// Uses the content of the variable as a proof (predicate argument).
+    pub const fn proof(self) -> Handle<Proof> {
+        Handle::from_raw(self.raw)
+    }
+
+    /// Uses the content of the variable as a PubKey hash.
+    pub const fn pub_key_hash(self) -> Handle<PubKeyHash> {
+        Handle::from_raw(self.raw)
+    }
+}
+
+impl From<Handle<Bytes>> for Handle<Proof> {
+    fn from(h: Handle<Bytes>) -> Self {
+        h.proof()
+    }
+}
+
+impl From<Handle<Bytes>> for Handle<PubKeyHash> {
+    fn from(h: Handle<Bytes>) -> Self {
+        h.pub_key_hash()
+    }
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Handle<T> {}
+
+/// Invalid (zero) handle, the host never assigns it to a variable.
+impl<T> Default for Handle<T> {
+    fn default() -> Self {
+        Self::from_raw(0)
+    }
+}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.raw == other.raw
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Handle({})", self.raw)
+    }
+}
+
+impl<T> From<Handle<T>> for ABHandle {
+    fn from(h: Handle<T>) -> Self {
+        h.raw
+    }
+}
+
+/// Marker of the variables containing raw (usually CBOR encoded) data.
+pub trait RawData: sealed::Sealed {}
+
+/// Marker of the native Alphabill objects.
+pub trait Object: sealed::Sealed {
+    /// type ID of the object, see [`evaluation_ctx::create_obj`](crate::evaluation_ctx::create_obj).
+    const TYPE_ID: u16;
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// the transaction order object, see [`TxOrder`](crate::txsystem::TxOrder).
+pub enum TxOrder {}
+
+/// variable containing raw bytes, usually CBOR encoded data.
+pub enum Bytes {}
+
+/// proof (argument) for a predicate, ie signature for the P2PKH predicate.
+pub enum Proof {}
+
+/// PubKey hash, ie the configuration of the P2PKH predicate.
+pub enum PubKeyHash {}
+
+/// configuration of the predicate.
+pub enum Config {}
+
+/// the transaction record object, see [`TxRecord`](crate::txsystem::TxRecord).
+pub enum TxRecord {}
+
+/// the transaction proof object, see [`TxProof`](crate::txsystem::TxProof).
+pub enum TxProof {}
+
+macro_rules! raw_data {
+    ($($t:ty),*) => {$(
+        impl sealed::Sealed for $t {}
+        impl RawData for $t {}
+    )*};
+}
+
+raw_data!(Bytes, Proof, PubKeyHash, Config);
+
+impl sealed::Sealed for TxRecord {}
+impl Object for TxRecord {
+    const TYPE_ID: u16 = txsystem::OBJ_TX_RECORD;
+}
+
+impl sealed::Sealed for TxProof {}
+impl Object for TxProof {
+    const TYPE_ID: u16 = txsystem::OBJ_TX_PROOF;
+}
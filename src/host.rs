@@ -3,3 +3,6 @@ Functions to communicate with the host executing the predicate.
 */
 
 pub mod logger;
+
+#[cfg(feature = "mock-host")]
+pub mod mock;
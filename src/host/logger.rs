@@ -25,14 +25,17 @@ pub fn debug(msg: &str) {
 }
 
 fn log(level: u32, msg: &str) {
-    let p = msg.as_ptr();
-    let addr = memory::pack_pointer((p as usize) as u32, msg.len());
+    let addr = memory::pack_slice(msg.as_bytes());
     unsafe { _log_host(level, addr) };
 }
 
+#[cfg(not(feature = "mock-host"))]
 #[link(wasm_import_module = "host")]
 unsafe extern "C" {
     /// logs the data (pointed by data_ptr) on level "lvl" using host logger
     #[link_name = "log_msg"]
     fn _log_host(lvl: u32, data_ptr: u64);
 }
+
+#[cfg(feature = "mock-host")]
+use crate::host::mock::host::*;
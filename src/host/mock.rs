@@ -0,0 +1,752 @@
+/*!
+Pure Rust implementation of the host APIs for native (non-wasm32) targets.
+
+When the `mock-host` feature is enabled the WASM imports of the SDK are
+replaced with the functions of this module so predicates can be exercised
+with `cargo test`. The state of the "host" is kept per thread and is set up
+using the [`MockHost`] builder before calling the predicate function, ie
+```
+use alphabill::{
+    api::{SignedByResult, signed_by_pkh},
+    cbor, evaluation_ctx, predicate_result,
+    host::mock::MockHost,
+};
+
+#[unsafe(no_mangle)]
+pub extern "C" fn my_predicate() -> u64 {
+    let cfg = cbor::parse_array(evaluation_ctx::HANDLE_CONFIG);
+    match signed_by_pkh(evaluation_ctx::HANDLE_TX_ORDER, cfg[1].pub_key_hash(), evaluation_ctx::HANDLE_ARGUMENT) {
+        SignedByResult::True => predicate_result!(true),
+        _ => predicate_result!(false),
+    }
+}
+
+MockHost::new()
+    .tx_order(5, 22, &[1, 2, 3])
+    // CBOR array [100, h'0102']
+    .config(&[0x82, 0x18, 0x64, 0x42, 0x01, 0x02])
+    .argument(&[0xAA, 0xBB])
+    .tx_signed_by_pkh(&[1, 2], &[0xAA, 0xBB], SignedByResult::True)
+    .install();
+assert_eq!(my_predicate(), 0);
+```
+
+The mock implements the host behavior only to the extent needed for testing,
+ie signatures are not verified, instead the outcome of the [`signed_by_pkh`]
+host call is looked up from the list of outcomes registered with the builder.
+
+Packed pointers (see [`memory`]) returned by the mock are not real addresses
+but refer to the buffers kept by the mock (native pointers do not fit into
+32 bits). Buffers returned by the mock are released when the [`HostBuf`]
+wrapping them is dropped (the mock panics on double free and on the use of
+released buffer), the rest of the buffers are released only when the thread
+exits.
+
+[`HostBuf`]: crate::memory::HostBuf
+
+[`signed_by_pkh`]: crate::api::signed_by_pkh
+[`memory`]: crate::memory
+*/
+
+extern crate alloc;
+extern crate std;
+
+use alloc::{boxed::Box, collections::BTreeMap, string::String, vec::Vec};
+use core::cell::RefCell;
+
+use crate::{
+    api::SignedByResult,
+    cbor::Item,
+    decoder::Value,
+    evaluation_ctx::{ABHandle, HANDLE_ARGUMENT, HANDLE_CONFIG, HANDLE_TX_ORDER},
+    memory,
+};
+
+/// handle of the first variable created while predicate is evaluated
+const FIRST_VAR_HANDLE: ABHandle = 4;
+
+std::thread_local! {
+    static STATE: RefCell<State> = RefCell::new(MockHost::new().state());
+    static BUFFERS: RefCell<Vec<Option<Box<[u64]>>>> = const { RefCell::new(Vec::new()) };
+}
+
+/**
+Builder for the state of the mock host.
+
+Calling [`install`](MockHost::install) makes the state current for the
+calling thread, any state installed previously is discarded.
+*/
+#[derive(Default)]
+pub struct MockHost {
+    partition: u32,
+    typ: u32,
+    unit_id: Vec<u8>,
+    network_id: u32,
+    ref_number: Option<Vec<u8>>,
+    timeout: Option<u64>,
+    max_fee: Option<u64>,
+    fee_credit_record_id: Option<Vec<u8>>,
+    auth_proof: Option<Vec<u8>>,
+    fee_proof: Option<Vec<u8>>,
+    attributes: Vec<u8>,
+    argument: Vec<u8>,
+    config: Vec<u8>,
+    round: u64,
+    time: u64,
+    unit_data: BTreeMap<Vec<u8>, Vec<u8>>,
+    signed_by: Vec<(Vec<u8>, Vec<u8>, u32)>,
+    transferred: Vec<(Vec<u8>, Option<Vec<u8>>, u64)>,
+    objects: Vec<(u16, Vec<u8>, Vec<u8>)>,
+    valid_proofs: Vec<(Vec<u8>, Vec<u8>)>,
+    evaluated: Vec<(Vec<u8>, Vec<u8>, u64)>,
+}
+
+impl MockHost {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// sets the main fields of the transaction order which triggered the predicate.
+    pub fn tx_order(mut self, partition: u32, typ: u32, unit_id: &[u8]) -> Self {
+        self.partition = partition;
+        self.typ = typ;
+        self.unit_id = unit_id.to_vec();
+        self
+    }
+
+    /// sets the reference number of the transaction order's client metadata.
+    pub fn ref_number(mut self, ref_number: &[u8]) -> Self {
+        self.ref_number = Some(ref_number.to_vec());
+        self
+    }
+
+    /// sets the network ID of the transaction order.
+    pub fn network_id(mut self, network_id: u32) -> Self {
+        self.network_id = network_id;
+        self
+    }
+
+    /// sets the timeout, max fee and fee credit record ID fields of the
+    /// transaction order's client metadata.
+    pub fn client_metadata(mut self, timeout: u64, max_fee: u64, fcr_id: &[u8]) -> Self {
+        self.timeout = Some(timeout);
+        self.max_fee = Some(max_fee);
+        self.fee_credit_record_id = Some(fcr_id.to_vec());
+        self
+    }
+
+    /// sets the auth proof and (optionally) fee proof of the transaction order.
+    pub fn proofs(mut self, auth_proof: &[u8], fee_proof: Option<&[u8]>) -> Self {
+        self.auth_proof = Some(auth_proof.to_vec());
+        self.fee_proof = fee_proof.map(|v| v.to_vec());
+        self
+    }
+
+    /// sets the attributes of the transaction order, the data must be encoded
+    /// as the host would serialize them, see [`Encoder`].
+    pub fn tx_attributes(mut self, data: Vec<u8>) -> Self {
+        self.attributes = data;
+        self
+    }
+
+    /// sets the (CBOR encoded) predicate argument, ie the value of the
+    /// [`HANDLE_ARGUMENT`] variable.
+    pub fn argument(mut self, data: &[u8]) -> Self {
+        self.argument = data.to_vec();
+        self
+    }
+
+    /// sets the (CBOR encoded) predicate configuration, ie the value of the
+    /// [`HANDLE_CONFIG`] variable.
+    pub fn config(mut self, data: &[u8]) -> Self {
+        self.config = data.to_vec();
+        self
+    }
+
+    /// sets the value returned by [`current_round`](crate::evaluation_ctx::current_round).
+    pub fn current_round(mut self, round: u64) -> Self {
+        self.round = round;
+        self
+    }
+
+    /// sets the value returned by [`current_time`](crate::evaluation_ctx::current_time).
+    pub fn current_time(mut self, time: u64) -> Self {
+        self.time = time;
+        self
+    }
+
+    /// sets the data of the unit, the data must be encoded as the host would
+    /// serialize it, see [`Encoder`]. The same data is returned for committed
+    /// and uncommitted state.
+    pub fn unit_data(mut self, unit_id: &[u8], data: Vec<u8>) -> Self {
+        self.unit_data.insert(unit_id.to_vec(), data);
+        self
+    }
+
+    /**
+    Registers the outcome of the [`signed_by_pkh`](crate::api::signed_by_pkh)
+    call for the given PubKey hash and proof.
+
+    When there is no outcome registered for the non-nil PKH and proof pair
+    [`SignedByResult::False`] is returned.
+    */
+    pub fn tx_signed_by_pkh(mut self, pkh: &[u8], proof: &[u8], result: SignedByResult) -> Self {
+        self.signed_by
+            .push((pkh.to_vec(), proof.to_vec(), result.code() as u32));
+        self
+    }
+
+    /**
+    Registers the amount returned by [`amount_transferred`](crate::api::amount_transferred)
+    for the receiver PubKey hash and reference number. The proof argument of the call
+    is not checked. Zero is returned when there is no amount registered for the
+    PKH and reference number pair.
+    */
+    pub fn amount_transferred(mut self, pkh: &[u8], ref_no: Option<&[u8]>, amount: u64) -> Self {
+        self.transferred
+            .push((pkh.to_vec(), ref_no.map(|r| r.to_vec()), amount));
+        self
+    }
+
+    /**
+    Registers the outcome of the [`satisfies`](crate::api::satisfies) call for
+    the given predicate and proof, `result` is encoded as the predicate would
+    return it (see [`predicate_result`](crate::predicate_result)).
+
+    When there is no outcome registered for the predicate and proof pair the
+    predicate evaluates to "false".
+    */
+    pub fn satisfies(mut self, predicate: &[u8], proof: &[u8], result: u64) -> Self {
+        self.evaluated
+            .push((predicate.to_vec(), proof.to_vec(), result));
+        self
+    }
+
+    /**
+    Registers native Alphabill object, ie when [`create_obj`] is called with
+    `type_id` and handle of the variable containing `cbor` the object created
+    serializes to `data` (which must be encoded as the host would serialize
+    the object, see [`Encoder`]).
+
+    [`create_obj`]: crate::evaluation_ctx::create_obj
+    */
+    pub fn object(mut self, type_id: u16, cbor: &[u8], data: Vec<u8>) -> Self {
+        self.objects.push((type_id, cbor.to_vec(), data));
+        self
+    }
+
+    /// registers CBOR encoded tx proof as valid proof of the (CBOR encoded)
+    /// tx record, any other proof is considered to be invalid.
+    pub fn valid_tx_proof(mut self, record: &[u8], proof: &[u8]) -> Self {
+        self.valid_proofs.push((record.to_vec(), proof.to_vec()));
+        self
+    }
+
+    /// makes the state current for the calling thread.
+    pub fn install(self) {
+        let state = self.state();
+        STATE.with(|s| *s.borrow_mut() = state);
+    }
+
+    fn state(self) -> State {
+        let mut txo = Encoder::new()
+            .u32(1, self.partition)
+            .bytes(2, &self.unit_id)
+            .u32(3, self.typ);
+        if let Some(rn) = &self.ref_number {
+            txo = txo.bytes(4, rn);
+        }
+        if self.network_id != 0 {
+            txo = txo.u32(5, self.network_id);
+        }
+        if let Some(v) = self.timeout {
+            txo = txo.u64(7, v);
+        }
+        if let Some(v) = self.max_fee {
+            txo = txo.u64(8, v);
+        }
+        if let Some(v) = &self.fee_credit_record_id {
+            txo = txo.bytes(9, v);
+        }
+        if let Some(v) = &self.auth_proof {
+            txo = txo.bytes(10, v);
+        }
+        if let Some(v) = &self.fee_proof {
+            txo = txo.bytes(11, v);
+        }
+
+        let mut vars = BTreeMap::new();
+        vars.insert(HANDLE_TX_ORDER.raw(), Var::TxOrder);
+        vars.insert(HANDLE_ARGUMENT.raw(), Var::Raw(self.argument));
+        vars.insert(HANDLE_CONFIG.raw(), Var::Raw(self.config));
+        State {
+            tx_order: txo.finish(),
+            attributes: self.attributes,
+            round: self.round,
+            time: self.time,
+            unit_data: self.unit_data,
+            signed_by: self.signed_by,
+            transferred: self.transferred,
+            objects: self.objects,
+            valid_proofs: self.valid_proofs,
+            evaluated: self.evaluated,
+            vars,
+            next_handle: FIRST_VAR_HANDLE,
+        }
+    }
+}
+
+/**
+Encodes data in the format host uses to serialize objects for the predicate,
+ie it is the counterpart of the [`TagValueIter`](crate::decoder::TagValueIter).
+```
+use alphabill::{decoder::{TagValueIter, Value}, host::mock::Encoder};
+
+let data = Encoder::new().u64(1, 42).string(2, "foo").finish();
+let mut fields = TagValueIter::new(&data);
+assert_eq!(fields.next(), Some((1, Value::U64(42))));
+```
+*/
+#[derive(Default)]
+pub struct Encoder {
+    buf: Vec<u8>,
+}
+
+impl Encoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn u32(self, tag: u8, v: u32) -> Self {
+        self.value(tag, &Value::U32(v))
+    }
+
+    pub fn u64(self, tag: u8, v: u64) -> Self {
+        self.value(tag, &Value::U64(v))
+    }
+
+    pub fn bytes(self, tag: u8, v: &[u8]) -> Self {
+        self.value(tag, &Value::Bytes(v.to_vec()))
+    }
+
+    pub fn string(self, tag: u8, v: &str) -> Self {
+        self.value(tag, &Value::String(v.into()))
+    }
+
+    pub fn value(mut self, tag: u8, v: &Value) -> Self {
+        self.buf.push(tag);
+        encode_value(&mut self.buf, v);
+        self
+    }
+
+    /// returns the encoded data.
+    pub fn finish(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+fn encode_value(buf: &mut Vec<u8>, v: &Value) {
+    match v {
+        Value::Bytes(b) => {
+            buf.push(1);
+            encode_slice(buf, b);
+        }
+        Value::U64(n) => {
+            buf.push(2);
+            buf.extend_from_slice(&n.to_le_bytes());
+        }
+        Value::U32(n) => {
+            buf.push(3);
+            buf.extend_from_slice(&n.to_le_bytes());
+        }
+        Value::String(s) => {
+            buf.push(4);
+            encode_slice(buf, s.as_bytes());
+        }
+        Value::Array(items) => {
+            buf.push(5);
+            buf.extend_from_slice(&(items.len() as u32).to_le_bytes());
+            for item in items {
+                encode_value(buf, item);
+            }
+        }
+        // type which is unknown for the decoder
+        Value::Error(_) => buf.push(0),
+    }
+}
+
+fn encode_slice(buf: &mut Vec<u8>, data: &[u8]) {
+    buf.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    buf.extend_from_slice(data);
+}
+
+struct State {
+    tx_order: Vec<u8>,
+    attributes: Vec<u8>,
+    round: u64,
+    time: u64,
+    unit_data: BTreeMap<Vec<u8>, Vec<u8>>,
+    signed_by: Vec<(Vec<u8>, Vec<u8>, u32)>,
+    transferred: Vec<(Vec<u8>, Option<Vec<u8>>, u64)>,
+    objects: Vec<(u16, Vec<u8>, Vec<u8>)>,
+    valid_proofs: Vec<(Vec<u8>, Vec<u8>)>,
+    evaluated: Vec<(Vec<u8>, Vec<u8>, u64)>,
+    vars: BTreeMap<ABHandle, Var>,
+    next_handle: ABHandle,
+}
+
+impl State {
+    fn add_var(&mut self, v: Var) -> ABHandle {
+        let h = self.next_handle;
+        self.next_handle += 1;
+        self.vars.insert(h, v);
+        h
+    }
+
+    /// returns CBOR item the variable represents.
+    fn item(&self, handle: ABHandle) -> Option<Item> {
+        match self.vars.get(&handle)? {
+            Var::Raw(data) => crate::cbor::decode(data).ok(),
+            Var::Item(item) => Some(item.clone()),
+            Var::TxOrder | Var::Obj { .. } => None,
+        }
+    }
+
+    /// returns the variable as byte slice, `None` when the variable doesn't
+    /// exist and `Some(None)` when the variable is CBOR nil.
+    fn bytes(&self, handle: ABHandle) -> Option<Option<Vec<u8>>> {
+        match self.vars.get(&handle)? {
+            Var::Raw(data) | Var::Item(Item::Bytes(data)) => Some(Some(data.clone())),
+            Var::Item(item) if item.is_null() => Some(None),
+            _ => Some(Some(Vec::new())),
+        }
+    }
+}
+
+/// host side variable
+#[derive(Clone)]
+enum Var {
+    TxOrder,
+    /// raw bytes, ie CBOR encoded argument or configuration
+    Raw(Vec<u8>),
+    /// decoded CBOR data item
+    Item(Item),
+    /// native object created from CBOR data, serializes to `data`
+    Obj {
+        cbor: Vec<u8>,
+        data: Vec<u8>,
+    },
+}
+
+/// converts CBOR data item into the value as encoded by the host.
+fn item_value(item: &Item) -> Value {
+    match item {
+        Item::Uint(n) => Value::U64(*n),
+        Item::Bytes(b) => Value::Bytes(b.clone()),
+        Item::Text(s) => Value::String(s.clone()),
+        Item::Array(items) => Value::Array(items.iter().map(item_value).collect()),
+        Item::Tag(_, item) => item_value(item),
+        // data types not supported by the mock
+        _ => Value::Error(0),
+    }
+}
+
+fn with_state<T>(f: impl FnOnce(&mut State) -> T) -> T {
+    STATE.with(|s| f(&mut s.borrow_mut()))
+}
+
+/// copies the data into mock's memory and returns "packed pointer" to it.
+pub(crate) fn store(data: &[u8]) -> u64 {
+    // use u64 as backing storage so that the buffer is suitably aligned
+    // for the arrays of handles too
+    let mut buf = alloc::vec![0u64; data.len().div_ceil(8)].into_boxed_slice();
+    unsafe {
+        core::ptr::copy_nonoverlapping(data.as_ptr(), buf.as_mut_ptr() as *mut u8, data.len())
+    };
+    let addr = BUFFERS.with(|b| {
+        let mut b = b.borrow_mut();
+        b.push(Some(buf));
+        b.len()
+    });
+    memory::pack_pointer(addr as u32, data.len())
+}
+
+/// returns raw pointer and size of the buffer the "packed pointer" refers to.
+pub(crate) fn load(ptr: u64) -> (*mut u8, usize) {
+    let (addr, size) = memory::unpack_pointer(ptr);
+    if addr == 0 {
+        return (core::ptr::NonNull::dangling().as_ptr(), 0);
+    }
+    BUFFERS.with(|b| match b.borrow_mut().get_mut(addr as usize - 1) {
+        Some(Some(buf)) => (buf.as_mut_ptr() as *mut u8, size),
+        Some(None) => panic!("use of freed buffer {addr}"),
+        None => panic!("invalid address {addr}"),
+    })
+}
+
+fn load_vec(ptr: u64) -> Vec<u8> {
+    memory::byte_array(ptr).to_vec()
+}
+
+fn store_handles(handles: &[ABHandle]) -> u64 {
+    let data: Vec<u8> = handles.iter().flat_map(|h| h.to_ne_bytes()).collect();
+    store(&data)
+}
+
+/// mock of the "host" module imports.
+pub(crate) mod host {
+    use super::*;
+
+    pub unsafe fn _log_host(lvl: u32, data_ptr: u64) {
+        let msg = load_vec(data_ptr);
+        std::eprintln!("[{lvl}] {}", String::from_utf8_lossy(&msg));
+    }
+
+    pub unsafe fn _ext_free(addr: u32) {
+        BUFFERS.with(|b| match b.borrow_mut().get_mut(addr as usize - 1) {
+            Some(buf @ Some(_)) => *buf = None,
+            Some(None) => panic!("double free of buffer {addr}"),
+            None => panic!("invalid address {addr}"),
+        })
+    }
+}
+
+/// mock of the "context" module imports.
+pub(crate) mod context {
+    use super::*;
+
+    pub unsafe fn _add_var(addr: u64) -> ABHandle {
+        let data = load_vec(addr);
+        with_state(|s| s.add_var(Var::Raw(data)))
+    }
+
+    pub unsafe fn _create_obj_h(type_id: u32, handle: ABHandle) -> ABHandle {
+        with_state(|s| {
+            // only the registered objects can be created out of raw data
+            let v = match s.vars.get(&handle)? {
+                Var::Raw(cbor) => s
+                    .objects
+                    .iter()
+                    .find(|(t, c, _)| *t as u32 == type_id && c == cbor)
+                    .map(|(_, cbor, data)| Var::Obj {
+                        cbor: cbor.clone(),
+                        data: data.clone(),
+                    })?,
+                v => v.clone(),
+            };
+            Some(s.add_var(v))
+        })
+        .unwrap_or(0)
+    }
+
+    pub unsafe fn _serialize_obj(handle: ABHandle, version: u8) -> u64 {
+        // versions the mock models: native objects are serialized with version 1,
+        // raw variables with version 0 as byte array and decoded CBOR items with
+        // version 0 as the decoded value (what `Decoder::from_handle` expected),
+        // the host returns no data for other versions.
+        let data = with_state(|s| {
+            let v = match (s.vars.get(&handle)?, version) {
+                (Var::TxOrder, 1) => return Some(s.tx_order.clone()),
+                (Var::Obj { data, .. }, 1) => return Some(data.clone()),
+                (Var::Raw(b), 0) => Value::Bytes(b.clone()),
+                (Var::Item(item), 0) => item_value(item),
+                _ => return None,
+            };
+            let mut buf = Vec::new();
+            encode_value(&mut buf, &v);
+            Some(buf)
+        });
+        data.map_or(0, |d| store(&d))
+    }
+
+    pub unsafe fn _tx_attributes(handle: ABHandle, _version: u8) -> u64 {
+        match with_state(|s| {
+            matches!(s.vars.get(&handle), Some(Var::TxOrder)).then(|| s.attributes.clone())
+        }) {
+            Some(d) => store(&d),
+            None => 0,
+        }
+    }
+
+    pub unsafe fn _current_round() -> u64 {
+        with_state(|s| s.round)
+    }
+
+    pub unsafe fn _current_time() -> u64 {
+        with_state(|s| s.time)
+    }
+
+    pub unsafe fn _unit_data(unit_id: u64, _committed: bool, _version: u8) -> u64 {
+        let id = load_vec(unit_id);
+        match with_state(|s| s.unit_data.get(&id).cloned()) {
+            Some(d) => store(&d),
+            None => 0,
+        }
+    }
+}
+
+/// mock of the "ab" module imports.
+pub(crate) mod ab {
+    use super::*;
+    #[cfg(feature = "mock-crypto")]
+    use crate::api::VerifyResult;
+    use sha2::{Digest, Sha256};
+
+    pub unsafe fn _amount_transferred(_tx_proof: ABHandle, pkh: ABHandle, ref_no: u64) -> u64 {
+        let ref_no = (ref_no != 0).then(|| load_vec(ref_no));
+        with_state(|s| {
+            let Some(Some(pkh)) = s.bytes(pkh) else {
+                return 0;
+            };
+            s.transferred
+                .iter()
+                .find(|(p, r, _)| *p == pkh && *r == ref_no)
+                .map_or(0, |(_, _, amount)| *amount)
+        })
+    }
+
+    pub unsafe fn _evaluate_predicate(txo: ABHandle, predicate: u64, proof: ABHandle) -> u64 {
+        let predicate = load_vec(predicate);
+        with_state(|s| {
+            if !matches!(s.vars.get(&txo), Some(Var::TxOrder)) {
+                return SignedByResult::InvalidHandleTxO.code();
+            }
+            let Some(proof) = s.bytes(proof) else {
+                return SignedByResult::InvalidHandleProof.code();
+            };
+            let proof = proof.unwrap_or_default();
+            s.evaluated
+                .iter()
+                .find(|(p, a, _)| *p == predicate && *a == proof)
+                .map_or(1, |(_, _, r)| *r)
+        })
+    }
+
+    pub unsafe fn _tx_signed_by_pkh(txo: ABHandle, pkh: ABHandle, proof: ABHandle) -> u32 {
+        with_state(|s| {
+            if !matches!(s.vars.get(&txo), Some(Var::TxOrder)) {
+                return SignedByResult::InvalidHandleTxO.code() as u32;
+            }
+            let pkh = match s.bytes(pkh) {
+                Some(Some(b)) => b,
+                Some(None) => return SignedByResult::NilPKH.code() as u32,
+                None => return SignedByResult::InvalidHandlePKH.code() as u32,
+            };
+            let proof = match s.bytes(proof) {
+                Some(Some(b)) => b,
+                Some(None) => return SignedByResult::NilProof.code() as u32,
+                None => return SignedByResult::InvalidHandleProof.code() as u32,
+            };
+            s.signed_by
+                .iter()
+                .find(|(k, p, _)| *k == pkh && *p == proof)
+                .map_or(SignedByResult::False.code() as u32, |(_, _, r)| *r)
+        })
+    }
+
+    pub unsafe fn _verify_tx_proof(proof: ABHandle, record: ABHandle) -> u32 {
+        with_state(|s| {
+            let (Some(Var::Obj { cbor: p, .. }), Some(Var::Obj { cbor: r, .. })) =
+                (s.vars.get(&proof), s.vars.get(&record))
+            else {
+                return 2;
+            };
+            let valid = s.valid_proofs.iter().any(|(vr, vp)| vr == r && vp == p);
+            (!valid) as u32
+        })
+    }
+
+    #[cfg(feature = "mock-crypto")]
+    pub unsafe fn _verify_secp256k1(pubkey: u64, msg_hash: u64, sig: u64) -> u32 {
+        use k256::ecdsa::{Signature, VerifyingKey, signature::hazmat::PrehashVerifier};
+
+        let Ok(key) = VerifyingKey::from_sec1_bytes(&load_vec(pubkey)) else {
+            return VerifyResult::InvalidPubKey.code() as u32;
+        };
+        // `r || s` optionally followed by the recovery ID
+        let sig = match load_vec(sig) {
+            sig if sig.len() == 64 => sig,
+            sig if sig.len() == 65 && sig[64] < 4 => sig[..64].to_vec(),
+            _ => return VerifyResult::InvalidSignature.code() as u32,
+        };
+        let Ok(sig) = Signature::from_slice(&sig) else {
+            return VerifyResult::InvalidSignature.code() as u32;
+        };
+        let hash = load_vec(msg_hash);
+        if hash.len() != 32 {
+            return VerifyResult::InvalidMsgHash.code() as u32;
+        }
+        match key.verify_prehash(&hash, &sig) {
+            Ok(()) => VerifyResult::True.code() as u32,
+            Err(_) => VerifyResult::False.code() as u32,
+        }
+    }
+
+    #[cfg(feature = "mock-crypto")]
+    pub unsafe fn _verify_ed25519(pubkey: u64, msg: u64, sig: u64) -> u32 {
+        use ed25519_dalek::{Signature, VerifyingKey};
+
+        let Ok(key) = <[u8; 32]>::try_from(load_vec(pubkey)) else {
+            return VerifyResult::InvalidPubKey.code() as u32;
+        };
+        let Ok(key) = VerifyingKey::from_bytes(&key) else {
+            return VerifyResult::InvalidPubKey.code() as u32;
+        };
+        let Ok(sig) = Signature::from_slice(&load_vec(sig)) else {
+            return VerifyResult::InvalidSignature.code() as u32;
+        };
+        match key.verify_strict(&load_vec(msg), &sig) {
+            Ok(()) => VerifyResult::True.code() as u32,
+            Err(_) => VerifyResult::False.code() as u32,
+        }
+    }
+
+    #[cfg(not(feature = "mock-crypto"))]
+    pub unsafe fn _verify_secp256k1(_pubkey: u64, _msg_hash: u64, _sig: u64) -> u32 {
+        panic!("signature verification requires the mock-crypto feature")
+    }
+
+    #[cfg(not(feature = "mock-crypto"))]
+    pub unsafe fn _verify_ed25519(_pubkey: u64, _msg: u64, _sig: u64) -> u32 {
+        panic!("signature verification requires the mock-crypto feature")
+    }
+
+    pub unsafe fn _digest_sha256(addr: u64) -> u64 {
+        let data = load_vec(addr);
+        store(&Sha256::digest(data))
+    }
+}
+
+/// mock of the "cbor" module imports.
+pub(crate) mod cbor {
+    use super::*;
+
+    pub unsafe fn _parse(handle: ABHandle, flag: u32) -> u64 {
+        let handles = with_state(|s| match s.item(handle) {
+            Some(Item::Array(items)) if flag == 0 => items
+                .into_iter()
+                .map(|item| s.add_var(Var::Item(item)))
+                .collect(),
+            Some(item) => alloc::vec![s.add_var(Var::Item(item))],
+            None => Vec::new(),
+        });
+        store_handles(&handles)
+    }
+
+    pub unsafe fn _chunks(handle: ABHandle) -> u64 {
+        let handles = with_state(|s| {
+            let Some(Some(data)) = s.bytes(handle) else {
+                return Vec::new();
+            };
+            // register raw bytes of every array item as new variable
+            let Ok(Item::Array(items)) = crate::cbor::decode(&data) else {
+                return Vec::new();
+            };
+            items
+                .iter()
+                .map(|item| s.add_var(Var::Raw(item.encode())))
+                .collect()
+        });
+        store_handles(&handles)
+    }
+}
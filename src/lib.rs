@@ -13,12 +13,20 @@ pub extern "C" fn predicate_name() -> u64 {
 Return value of the function must follow convention where:
  - `0`: predicate evaluated to "true";
  - `lowest byte of the value == 1`: predicate evaluated to "false", higher 7 bytes
-    can be used to encode the reason why predicate evaluated to `false`;
+   can be used to encode the reason why predicate evaluated to `false`;
  - any other value is "error code" (IOW predicate failed), predicate's documentation
    should list the meaning of each error code;
 
 It is recommended to use [`predicate_result`] macro for encoding the return value
-of the predicate.
+of the predicate or the [`predicate`] attribute macro which generates the export
+for a function returning `Result<bool, Error>`, ie
+```rust
+# use alphabill::{error::Error, predicate};
+#[predicate]
+fn predicate_name() -> Result<bool, Error> {
+    Ok(true)
+}
+```
 
 ## Project template
 
@@ -37,6 +45,22 @@ on how to use the output
 (`target/wasm32-unknown-unknown/release/crate_name.wasm`) as a
 predicate with an Alphabill transaction system unit.
 
+## Testing
+
+The host APIs are WASM imports so predicates can't be executed outside of the
+Alphabill node as is. When the `mock-host` feature is enabled the imports are
+replaced with pure Rust implementation which allows to unit-test predicates on
+native target with `cargo test`, see [`host::mock`] for details. Usually the
+feature is enabled only for tests, ie
+```toml
+[dev-dependencies.alphabill]
+default-features = false
+features = [ "mock-host" ]
+```
+Predicates which verify signatures (ie [`api::verify_ed25519`]) need the
+`mock-crypto` feature instead, it adds pure Rust implementation of the
+signature verification APIs to the mock.
+
 ## Features
 
 To help keep the binary size small some of the higher level data structures and functions
@@ -56,15 +80,300 @@ Feature flags available are:
 )]
 #![no_std]
 
+// allows to use the derive macros inside the SDK itself
+extern crate self as alphabill;
+
 pub mod api;
 pub mod cbor;
 pub mod decoder;
 pub mod error;
 pub mod evaluation_ctx;
+pub mod handle;
 pub mod host;
 pub mod memory;
 pub mod txsystem;
 
+/**
+Derive macro generating decoder for the tag-value encoded data.
+
+Generates `from(input: impl AsRef<[u8]>) -> Result<Self, Error>` method for
+the struct which decodes the fields marked with `#[ab(tag = N)]` attribute.
+The struct must implement [`Default`], fields without the attribute are left
+to their default value. Unknown tags in the input are ignored.
+
+Field type must implement `TryFrom<`[`Value`]`, Error = `[`Error`]`>`, for
+`Option<T>` fields the `T` must implement it and the field is set to `Some`
+when the tag is present in the input.
+When converting the value fails the tag number is chained to the error code.
+
+## Examples
+```
+use alphabill::AbDecode;
+
+#[derive(AbDecode, Default, Debug)]
+struct Config {
+    #[ab(tag = 1)]
+    threshold: u64,
+    #[ab(tag = 2)]
+    name: Option<String>,
+    // not decoded
+    cache: Vec<u8>,
+}
+
+// tag 1: U32(3)
+let cfg = Config::from([1, 3, 3, 0, 0, 0]).unwrap();
+assert_eq!(cfg.threshold, 3);
+assert_eq!(cfg.name, None);
+// tag 2 is not string but U32(1)
+let err = Config::from([1, 3, 3, 0, 0, 0, 2, 3, 1, 0, 0, 0]).unwrap_err();
+assert_eq!(err.code(), 0x0102);
+```
+
+[`Value`]: crate::decoder::Value
+[`Error`]: crate::error::Error
+*/
+pub use alphabill_derive::AbDecode;
+
+/**
+Attribute macro turning a function into exported predicate.
+
+Instead of encoding the return value with [`predicate_result`] macro the function
+may return any type implementing the [`PredicateResult`] trait, ie
+`Result<bool, Error>`, so the `?` operator can be used to return errors, or
+[`Verdict`] when "false" needs an informational code.
+The macro generates `#[unsafe(no_mangle)] pub extern "C" fn name() -> u64`
+export with the same name which converts the value using
+[`PredicateResult::into_u64`].
+
+Error codes the predicate may return can be listed using the `error(code, "description")`
+arguments, the list is added to the documentation of the function as "Errors" section.
+
+## Examples
+```
+use alphabill::{error::Error, predicate};
+
+/// predicate which is true when the argument is not empty
+#[predicate(error(0x0c, "failed to load the argument"))]
+fn has_argument() -> Result<bool, Error> {
+    let arg = load_argument().map_err(|err| err.chain(0x0c))?;
+    Ok(!arg.is_empty())
+}
+
+# fn load_argument() -> Result<Vec<u8>, Error> { Err(Error::new(2)) }
+assert_eq!(has_argument(), 0x020c);
+```
+
+Visibility of the function is preserved, ie private predicate can't be called
+outside of its module (the symbol is exported nevertheless):
+```compile_fail
+mod predicates {
+    #[alphabill::predicate]
+    fn private() -> bool { true }
+}
+
+predicates::private();
+```
+*/
+pub use alphabill_derive::predicate;
+
+/**
+Value which can be returned by a predicate function.
+
+The value is encoded according to the rules of the [`predicate_result`] macro.
+*/
+pub trait PredicateResult {
+    fn into_u64(self) -> u64;
+}
+
+impl PredicateResult for bool {
+    fn into_u64(self) -> u64 {
+        if self {
+            predicate_result!(true)
+        }
+        predicate_result!(false)
+    }
+}
+
+impl PredicateResult for error::Error {
+    fn into_u64(self) -> u64 {
+        Verdict::Error(self).into_u64()
+    }
+}
+
+impl<T: PredicateResult> PredicateResult for Result<T, error::Error> {
+    fn into_u64(self) -> u64 {
+        match self {
+            Ok(v) => v.into_u64(),
+            Err(err) => err.into_u64(),
+        }
+    }
+}
+
+/**
+Structured form of the value returned by a predicate.
+
+[`into_u64`](Verdict::into_u64) encodes the verdict the same way as the
+[`predicate_result`] macro and [`from_u64`](Verdict::from_u64) decodes the
+value the way the host interprets it, so test harnesses and off-chain tooling
+can inspect the raw result of a predicate, ie
+```
+use alphabill::{Verdict, error::Error};
+
+assert_eq!(Verdict::from_u64(0x0801), Verdict::False(8));
+assert_eq!(Verdict::Error(Error::new(2).chain(1)).into_u64(), 0x020100);
+```
+
+Decoding never loses information, `Verdict::from_u64(v).into_u64() == v` holds
+for every `v`. Encoding is lossy when the value can't be represented, ie the
+"false" code longer than 7 bytes is truncated and error code with the lowest
+byte `0x01` is shifted left so the decoded verdict contains the shifted code.
+*/
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    True,
+    /// predicate evaluated to "false", the informational code
+    False(u64),
+    /// evaluation of the predicate failed
+    Error(error::Error),
+}
+
+impl Verdict {
+    /// Encodes the verdict as the return value of the predicate.
+    pub fn into_u64(self) -> u64 {
+        match self {
+            Verdict::True => 0,
+            Verdict::False(code) => (code << 8) | 1,
+            // the value must not be 0 and the value of the low byte must not
+            // be 0x01 (so we wouldn't incorrectly interpret it as "false with
+            // informational code" on the host side). In that case we shift it
+            // to the left so return value is 0xnn00, in case of error code
+            // zero we return max u64!
+            Verdict::Error(err) => match err.code() {
+                0 => u64::MAX,
+                c if c & 0xFF == 1 => c << 8,
+                c => c,
+            },
+        }
+    }
+
+    /// Decodes the return value of the predicate.
+    pub fn from_u64(v: u64) -> Self {
+        match v {
+            0 => Verdict::True,
+            v if v & 0xFF == 1 => Verdict::False(v >> 8),
+            u64::MAX => Verdict::Error(error::Error::new(0)),
+            v => Verdict::Error(error::Error::from_code(v)),
+        }
+    }
+}
+
+impl PredicateResult for Verdict {
+    fn into_u64(self) -> u64 {
+        Verdict::into_u64(self)
+    }
+}
+
+impl From<bool> for Verdict {
+    fn from(v: bool) -> Self {
+        if v { Verdict::True } else { Verdict::False(0) }
+    }
+}
+
+impl From<error::Error> for Verdict {
+    fn from(err: error::Error) -> Self {
+        Verdict::Error(err)
+    }
+}
+
+/**
+Derive macro generating decoder for the predicate configuration or argument.
+
+Implements [`FromItem`] and [`PredicateConfig`] traits for the struct, the
+struct is decoded from CBOR array where the items are assigned to the fields
+in the order the fields are declared. Use the [`evaluation_ctx::config`] or
+[`evaluation_ctx::argument`] function to load the struct.
+
+Field type must implement [`FromItem`], nested arrays can be decoded into
+another struct deriving `PredicateConfig`. Behavior of the field can be
+customized using the `#[config(...)]` attribute:
+ - `index = N`: position of the item (starting from `0`) in the array. Fields
+   disabled by `#[cfg]` are not visible to the macro so the fields with `#[cfg]`
+   attribute and all the fields following them must set it explicitly;
+ - `error = N`: code to chain to the error when decoding the field fails,
+   by default the position of the item (starting from `1`) is used;
+ - `handle`: field of the type [`Handle<T>`] (or `Option<Handle<T>>`) where
+   `T` is [`Bytes`], [`Proof`] or [`PubKeyHash`], host variable is created from
+   the item and the field is assigned its handle (see [`cbor::add_var`]);
+ - `default`: when the item is missing use the default value of the type;
+ - `rest`: the field is `Vec<T>` which collects all the remaining items,
+   must be the last field of the struct.
+
+`Option` fields are set to `None` when the item is missing or is `null`, extra
+items in the array are ignored unless the struct has `#[config(len = N)]`
+attribute which requires the array to have exactly `N` items.
+
+## Examples
+```
+use alphabill::{PredicateConfig, cbor::FromItem, cbor, handle::{Handle, PubKeyHash}};
+
+#[derive(PredicateConfig)]
+struct Prices {
+    early_bird: u64,
+    regular: u64,
+}
+
+#[derive(PredicateConfig)]
+struct Config {
+    #[config(error = 0x0c)]
+    prices: Prices,
+    #[config(handle)]
+    pkh: Handle<PubKeyHash>,
+    /// optional trailing item
+    note: Option<String>,
+}
+
+# #[cfg(feature = "mock-host")] {
+// [[10, 20], h'0102']
+let cfg = cbor::decode(&[0x82, 0x82, 0x0a, 0x14, 0x42, 1, 2]).unwrap();
+let cfg = Config::from_item(&cfg).unwrap();
+assert_eq!(cfg.prices.regular, 20);
+assert_eq!(cfg.note, None);
+
+// [[10, h'00'], h'0102']
+let cfg = cbor::decode(&[0x82, 0x82, 0x0a, 0x41, 0x00, 0x42, 1, 2]).unwrap();
+let err = Config::from_item(&cfg).err().unwrap();
+// ERR_INVALID_TYPE of the second item of the field with error code 0x0c
+assert_eq!(err.code(), 0x07_02_0c);
+# }
+```
+
+Position of the item following a `#[cfg]` field must be set explicitly as
+otherwise it would depend on the enabled features:
+```compile_fail
+use alphabill::PredicateConfig;
+
+#[derive(PredicateConfig)]
+struct Config {
+    #[cfg(true)]
+    #[config(index = 0)]
+    price: u64,
+    // error: missing #[config(index = 1)]
+    note: String,
+}
+```
+
+[`FromItem`]: crate::cbor::FromItem
+[`PredicateConfig`]: crate::evaluation_ctx::PredicateConfig
+[`Handle<T>`]: crate::handle::Handle
+[`Bytes`]: crate::handle::Bytes
+[`Proof`]: crate::handle::Proof
+[`PubKeyHash`]: crate::handle::PubKeyHash
+*/
+pub use alphabill_derive::PredicateConfig;
+
+#[cfg(all(feature = "mock-host", target_arch = "wasm32"))]
+compile_error!("the \"mock-host\" feature is meant for native targets only");
+
 #[cfg(not(test))]
 #[cfg(target_arch = "wasm32")]
 #[cfg(feature = "panic-handler")]
@@ -80,15 +389,18 @@ Macro helps to correctly encode the return value of a predicate, it has
 three main forms:
  - `predicate_result!(true)` -- predicate returns "true" (encoded as `0`);
  - `predicate_result!(false, [code])` -- predicate returns "false", an
-    optional information code (integer) can be sent as second parameter.
-    Return value will have lowest byte set to `1`, higher bytes contain the
-    optional code value. Ie `predicate_result!(false, 8)` would return `0x0801`.
-    When the "code" is longer than 7 bytes higher bytes will be lost;
+   optional information code (integer) can be sent as second parameter.
+   Return value will have lowest byte set to `1`, higher bytes contain the
+   optional code value. Ie `predicate_result!(false, 8)` would return `0x0801`.
+   When the "code" is longer than 7 bytes higher bytes will be lost;
  - `predicate_result!(Error)` -- predicate returns error (ie abnormal end of
-    evaluation), argument must be of type [`Error`]. The `code` value of the
-    error is returned unless the lowest byte of the code equals to `1` -- in that
-    case the value is shifted left 8 bits so the lowest byte would equal to `0`.
-    In case of error code zero we will return max u64 (iow all bytes set to 0xFF).
+   evaluation), argument must be of type [`Error`]. The `code` value of the
+   error is returned unless the lowest byte of the code equals to `1` -- in that
+   case the value is shifted left 8 bits so the lowest byte would equal to `0`.
+   In case of error code zero we will return max u64 (iow all bytes set to 0xFF).
+
+   Any other type implementing [`PredicateResult`] (ie [`Verdict`]) is accepted
+   in place of the [`Error`] too.
 
 ## Examples
 ```
@@ -118,28 +430,16 @@ macro_rules! predicate_result {
     (false, $code:expr) => {
         return ($code << 8) | 1
     };
-    // when Error is returned make sure that the return value is not 0 or the
-    // value of the low byte is not 0x01 (so we wouldn't incorrectly interpret
-    // it as "false with informational code" on the host side).
-    // in that case (last byte == 1) we just shift it to the left so return
-    // value is 0xnn00. In case of error code zero we will return max u64!
-    ($err:expr) => {{
-        let c = $err.code();
-        if c == 0 {
-            return u64::MAX;
-        }
-        if c & 0xFF == 1 {
-            return c << 8;
-        }
-        return c;
-    };};
+    // Error (or Verdict etc) is encoded by the PredicateResult implementation
+    ($err:expr) => {
+        return $crate::PredicateResult::into_u64($err)
+    };
 }
 
 #[cfg(test)]
 mod tests {
 
-    use super::*;
-    use crate::error::Error;
+    use crate::{Verdict, error::Error};
 
     fn pr_true() -> u64 {
         predicate_result!(true);
@@ -175,4 +475,44 @@ mod tests {
         assert_eq!(0x020100, pr_error(Error::new(2).chain(1)));
         assert_eq!(u64::MAX, pr_error(Error::new(0)));
     }
+
+    #[test]
+    fn predicate_result_trait() {
+        use crate::PredicateResult;
+
+        assert_eq!(0, true.into_u64());
+        assert_eq!(1, false.into_u64());
+        assert_eq!(0, Ok::<_, Error>(true).into_u64());
+        assert_eq!(1, Ok::<_, Error>(false).into_u64());
+        assert_eq!(0x04, Err::<bool, _>(Error::new(4)).into_u64());
+        assert_eq!(0x020100, Err::<bool, _>(Error::new(2).chain(1)).into_u64());
+        assert_eq!(u64::MAX, Error::new(0).into_u64());
+        assert_eq!(0x0801, Verdict::False(8).into_u64());
+    }
+
+    fn pr_verdict(v: Verdict) -> u64 {
+        predicate_result!(v);
+    }
+
+    #[test]
+    fn verdict() {
+        assert_eq!(0, pr_verdict(Verdict::True));
+        assert_eq!(0x0401, pr_verdict(Verdict::False(4)));
+        assert_eq!(0x020100, pr_verdict(Verdict::Error(Error::new(2).chain(1))));
+
+        assert_eq!(Verdict::from_u64(0), Verdict::True);
+        assert_eq!(Verdict::from_u64(1), Verdict::False(0));
+        assert_eq!(Verdict::from_u64(0x12ff01), Verdict::False(0x12ff));
+        assert_eq!(Verdict::from_u64(4), Verdict::Error(Error::new(4)));
+        // shifted error code is decoded as is
+        assert_eq!(
+            Verdict::from_u64(0x020100),
+            Verdict::Error(Error::from_code(0x020100))
+        );
+        assert_eq!(Verdict::from_u64(u64::MAX), Verdict::Error(Error::new(0)));
+
+        for v in [0, 1, 0x0801, 4, 0x0100, 0x020100, u64::MAX, u64::MAX - 1] {
+            assert_eq!(Verdict::from_u64(v).into_u64(), v);
+        }
+    }
 }
@@ -2,8 +2,7 @@
 Memory management support.
 */
 
-use alloc::vec::Vec;
-extern crate alloc;
+use core::{fmt, marker::PhantomData, ops::Deref};
 
 /// return (address, size) pair for the "packed pointer"
 pub fn unpack_pointer(ptr: u64) -> (u32, usize) {
@@ -15,19 +14,106 @@ pub fn pack_pointer(addr: u32, size: usize) -> u64 {
     ((size as u64) << 32) | (addr as u64)
 }
 
+/// returns "packed pointer" of the data so it can be sent to the host.
+#[cfg(not(feature = "mock-host"))]
+pub fn pack_slice(data: &[u8]) -> u64 {
+    pack_pointer((data.as_ptr() as usize) as u32, data.len())
+}
+
+/// returns "packed pointer" of the data so it can be sent to the host.
+///
+/// Native pointers do not fit into 32 bits so the data is copied into
+/// the mock host's memory.
+#[cfg(feature = "mock-host")]
+pub fn pack_slice(data: &[u8]) -> u64 {
+    crate::host::mock::store(data)
+}
+
 /// given the "packed pointer" in shared memory returns "Rust pointer"
 /// to the data as u8 slice.
+///
+/// The caller is responsible for the lifetime of the memory, buffers returned
+/// by the host should be wrapped into [`HostBuf`] instead.
 pub fn byte_array<'a>(ptr: u64) -> &'a mut [u8] {
-    let (addr, size) = unpack_pointer(ptr);
-    let data: *mut u8 = addr as *mut u8;
+    let (data, size) = raw_parts(ptr);
     let buf: &mut [u8] = unsafe { core::slice::from_raw_parts_mut(data, size) };
     buf
 }
 
-pub fn load_bytes(ptr: u64) -> Vec<u8> {
-    byte_array(ptr).to_vec()
+#[cfg(not(feature = "mock-host"))]
+fn raw_parts(ptr: u64) -> (*mut u8, usize) {
+    let (addr, size) = unpack_pointer(ptr);
+    (addr as *mut u8, size)
+}
+
+#[cfg(feature = "mock-host")]
+fn raw_parts(ptr: u64) -> (*mut u8, usize) {
+    crate::host::mock::load(ptr)
 }
 
+/**
+Buffer allocated by the host and returned to the predicate.
+
+The buffer dereferences to slice of `T` (bytes by default, handles for the
+APIs returning arrays of handles) and the memory is released (`ext_free`)
+when the buffer is dropped.
+*/
+pub struct HostBuf<T: Copy = u8> {
+    ptr: u64,
+    _t: PhantomData<T>,
+}
+
+impl<T: Copy> HostBuf<T> {
+    /**
+    Takes ownership of the buffer the "packed pointer" refers to.
+
+    # Safety
+    The `ptr` must be returned by the host API which allocates the buffer for
+    the predicate (or be zero) and the buffer must not be owned by anything else.
+    The address must be suitably aligned for `T`.
+    */
+    pub unsafe fn from_raw(ptr: u64) -> Self {
+        Self {
+            ptr,
+            _t: PhantomData,
+        }
+    }
+}
+
+impl<T: Copy> Deref for HostBuf<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        let (data, size) = raw_parts(self.ptr);
+        if size < size_of::<T>() {
+            return &[];
+        }
+        unsafe { core::slice::from_raw_parts(data as *const T, size / size_of::<T>()) }
+    }
+}
+
+impl<T: Copy> AsRef<[T]> for HostBuf<T> {
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T: Copy + fmt::Debug> fmt::Debug for HostBuf<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: Copy> Drop for HostBuf<T> {
+    fn drop(&mut self) {
+        let (addr, _) = unpack_pointer(self.ptr);
+        if addr != 0 {
+            unsafe { _ext_free(addr) }
+        }
+    }
+}
+
+#[cfg(not(feature = "mock-host"))]
 #[link(wasm_import_module = "host")]
 unsafe extern "C" {
     /// returns address of allocated block.
@@ -37,28 +123,424 @@ unsafe extern "C" {
     /// frees memory allocated on given address.
     #[link_name = "ext_free"]
     fn _ext_free(addr: u32);
+
+    /// resizes the block allocated on given address, returns address of the
+    /// (possibly moved) block.
+    #[cfg(feature = "host-realloc")]
+    #[link_name = "ext_realloc"]
+    fn _ext_realloc(addr: u32, size: u32) -> u32;
+}
+
+#[cfg(feature = "mock-host")]
+use crate::host::mock::host::_ext_free;
+
+/**
+Alignment of the blocks returned by the host's `ext_malloc` and `ext_realloc`.
+
+This is part of the host contract: the host must return blocks aligned to (at
+least) 8 bytes. Layouts with alignment up to this are passed to the host as is
+so a host returning less aligned blocks would break them, layouts with bigger
+alignment are over-allocated and aligned by the allocator itself.
+*/
+#[cfg(any(test, all(target_arch = "wasm32", not(feature = "bump-alloc"))))]
+const HOST_ALIGN: usize = 8;
+
+/// size of the block to request from the host for the layout, blocks with
+/// alignment bigger than [`HOST_ALIGN`] are over-allocated so that aligned
+/// address and the address of the original block fit in.
+#[cfg(any(test, all(target_arch = "wasm32", not(feature = "bump-alloc"))))]
+fn host_size(layout: core::alloc::Layout) -> usize {
+    if layout.align() <= HOST_ALIGN {
+        return layout.size();
+    }
+    layout.size() + layout.align() + size_of::<usize>()
+}
+
+/// returns aligned address inside the (over-allocated) block `raw`, the
+/// address of the block is stored right before the returned address.
+#[cfg(any(test, all(target_arch = "wasm32", not(feature = "bump-alloc"))))]
+unsafe fn align_block(raw: *mut u8, layout: core::alloc::Layout) -> *mut u8 {
+    if raw.is_null() || layout.align() <= HOST_ALIGN {
+        return raw;
+    }
+    let offset =
+        (raw as usize + size_of::<usize>()).next_multiple_of(layout.align()) - raw as usize;
+    unsafe {
+        let ptr = raw.add(offset);
+        (ptr.sub(size_of::<usize>()) as *mut usize).write_unaligned(raw as usize);
+        ptr
+    }
+}
+
+/// reverse of [`align_block`], returns address of the block allocated by host.
+#[cfg(any(test, all(target_arch = "wasm32", not(feature = "bump-alloc"))))]
+unsafe fn raw_block(ptr: *mut u8, layout: core::alloc::Layout) -> *mut u8 {
+    if layout.align() <= HOST_ALIGN {
+        return ptr;
+    }
+    unsafe { (ptr.sub(size_of::<usize>()) as *const usize).read_unaligned() as *mut u8 }
+}
+
+/// resizes the block allocated for `layout` using the host's `malloc`, `realloc`
+/// and `free` functions, see [`HOST_ALIGN`].
+#[cfg(any(
+    test,
+    all(
+        target_arch = "wasm32",
+        not(feature = "bump-alloc"),
+        feature = "host-realloc"
+    )
+))]
+unsafe fn realloc_block(
+    ptr: *mut u8,
+    layout: core::alloc::Layout,
+    new_size: usize,
+    malloc: impl FnOnce(usize) -> *mut u8,
+    realloc: impl FnOnce(*mut u8, usize) -> *mut u8,
+    free: impl FnOnce(*mut u8),
+) -> *mut u8 {
+    if layout.align() <= HOST_ALIGN {
+        return realloc(ptr, new_size);
+    }
+    // the offset of the aligned address might change when the
+    // block is moved so allocate new block and copy the data
+    let new_layout =
+        unsafe { core::alloc::Layout::from_size_align_unchecked(new_size, layout.align()) };
+    let new_ptr = unsafe { align_block(malloc(host_size(new_layout)), new_layout) };
+    if !new_ptr.is_null() {
+        unsafe {
+            core::ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size));
+            free(raw_block(ptr, layout));
+        }
+    }
+    new_ptr
 }
 
 #[cfg(not(test))]
 #[cfg(target_arch = "wasm32")]
+#[cfg(not(feature = "bump-alloc"))]
 #[global_allocator]
 static ALLOCATOR: WasmAllocator = WasmAllocator;
 
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(target_arch = "wasm32", not(feature = "bump-alloc")))]
 struct WasmAllocator;
 
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(target_arch = "wasm32", not(feature = "bump-alloc")))]
 mod allocator_impl {
     use super::*;
     use core::alloc::{GlobalAlloc, Layout};
 
     unsafe impl GlobalAlloc for WasmAllocator {
         unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
-            unsafe { _ext_malloc(layout.size() as u32) as *mut u8 }
+            unsafe {
+                let raw = _ext_malloc(host_size(layout) as u32) as *mut u8;
+                align_block(raw, layout)
+            }
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            unsafe { _ext_free(raw_block(ptr, layout) as u32) }
+        }
+
+        #[cfg(feature = "host-realloc")]
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            unsafe {
+                realloc_block(
+                    ptr,
+                    layout,
+                    new_size,
+                    |size| _ext_malloc(size as u32) as *mut u8,
+                    |ptr, size| _ext_realloc(ptr as u32, size as u32) as *mut u8,
+                    |ptr| _ext_free(ptr as u32),
+                )
+            }
+        }
+    }
+}
+
+/**
+Bump allocator, memory is never returned to the host (except when the most
+recent allocation is released or resized) - the whole arena is dropped when
+the predicate exits.
+
+New memory is requested in pages using `grow` callback which returns the
+address of the new pages (or `None` when out of memory).
+*/
+#[cfg(any(test, all(target_arch = "wasm32", feature = "bump-alloc")))]
+struct BumpArena {
+    /// next free address
+    next: usize,
+    /// end of the current region
+    end: usize,
+    /// address of the most recent allocation
+    last: usize,
+}
+
+#[cfg(any(test, all(target_arch = "wasm32", feature = "bump-alloc")))]
+impl BumpArena {
+    const PAGE_SIZE: usize = 65536;
+
+    const fn new() -> Self {
+        Self {
+            next: 0,
+            end: 0,
+            last: 0,
+        }
+    }
+
+    fn alloc(
+        &mut self,
+        layout: core::alloc::Layout,
+        grow: impl FnOnce(usize) -> Option<usize>,
+    ) -> *mut u8 {
+        let mut start = self.next.next_multiple_of(layout.align());
+        if self.next == 0 || start + layout.size() > self.end {
+            let pages = (layout.size() + layout.align()).div_ceil(Self::PAGE_SIZE);
+            let Some(addr) = grow(pages) else {
+                return core::ptr::null_mut();
+            };
+            // when the new pages are not continuation of the current region
+            // the rest of the current region is wasted
+            if addr != self.end {
+                self.next = addr;
+            }
+            self.end = addr + pages * Self::PAGE_SIZE;
+            start = self.next.next_multiple_of(layout.align());
+        }
+        self.last = start;
+        self.next = start + layout.size();
+        start as *mut u8
+    }
+
+    fn dealloc(&mut self, ptr: *mut u8) {
+        if ptr as usize == self.last {
+            self.next = self.last;
+        }
+    }
+
+    /// resizes the most recent allocation in place, returns `false` when it
+    /// is not possible (the block needs to be moved).
+    fn resize(&mut self, ptr: *mut u8, new_size: usize) -> bool {
+        if ptr as usize != self.last || self.last + new_size > self.end {
+            return false;
+        }
+        self.next = self.last + new_size;
+        true
+    }
+}
+
+#[cfg(not(test))]
+#[cfg(all(target_arch = "wasm32", feature = "bump-alloc"))]
+#[global_allocator]
+static ALLOCATOR: BumpAllocator = BumpAllocator(core::cell::UnsafeCell::new(BumpArena::new()));
+
+#[cfg(all(target_arch = "wasm32", feature = "bump-alloc"))]
+struct BumpAllocator(core::cell::UnsafeCell<BumpArena>);
+
+// WASM predicates are single threaded
+#[cfg(all(target_arch = "wasm32", feature = "bump-alloc"))]
+unsafe impl Sync for BumpAllocator {}
+
+#[cfg(all(target_arch = "wasm32", feature = "bump-alloc"))]
+mod bump_impl {
+    use super::*;
+    use core::alloc::{GlobalAlloc, Layout};
+
+    fn grow(pages: usize) -> Option<usize> {
+        match core::arch::wasm32::memory_grow(0, pages) {
+            usize::MAX => None,
+            page => Some(page * BumpArena::PAGE_SIZE),
+        }
+    }
+
+    unsafe impl GlobalAlloc for BumpAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            unsafe { (*self.0.get()).alloc(layout, grow) }
         }
 
         unsafe fn dealloc(&self, ptr: *mut u8, _: Layout) {
-            unsafe { _ext_free(ptr as u32) }
+            unsafe { (*self.0.get()).dealloc(ptr) }
         }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            if unsafe { (*self.0.get()).resize(ptr, new_size) } {
+                return ptr;
+            }
+            let new_layout = unsafe { Layout::from_size_align_unchecked(new_size, layout.align()) };
+            let new_ptr = unsafe { self.alloc(new_layout) };
+            if !new_ptr.is_null() {
+                unsafe {
+                    core::ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size))
+                };
+            }
+            new_ptr
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::alloc::Layout;
+
+    #[test]
+    fn aligned_block() {
+        let mut mem = [0u64; 16];
+        // address aligned to HOST_ALIGN, as returned by the host
+        let raw = mem.as_mut_ptr() as *mut u8;
+
+        let layout = Layout::from_size_align(10, 4).unwrap();
+        assert_eq!(host_size(layout), 10);
+        let ptr = unsafe { align_block(raw, layout) };
+        assert_eq!(ptr, raw);
+        assert_eq!(unsafe { raw_block(ptr, layout) }, raw);
+
+        for align in [16, 32, 64] {
+            let layout = Layout::from_size_align(10, align).unwrap();
+            assert_eq!(host_size(layout), 10 + align + size_of::<usize>());
+            let ptr = unsafe { align_block(raw, layout) };
+            assert_eq!(ptr as usize % align, 0);
+            assert!(ptr as usize >= raw as usize + size_of::<usize>());
+            assert!(ptr as usize + 10 <= raw as usize + host_size(layout));
+            assert_eq!(unsafe { raw_block(ptr, layout) }, raw);
+        }
+
+        let layout = Layout::from_size_align(10, 32).unwrap();
+        assert!(unsafe { align_block(core::ptr::null_mut(), layout) }.is_null());
+    }
+
+    #[test]
+    fn realloc_aligned_block() {
+        let mut old = [0u64; 16];
+        let mut new = [0u64; 32];
+        let (old_raw, new_raw) = (old.as_mut_ptr() as *mut u8, new.as_mut_ptr() as *mut u8);
+        let freed = core::cell::Cell::new(core::ptr::null_mut());
+
+        // block with alignment above HOST_ALIGN is moved to the new host block
+        let layout = Layout::from_size_align(10, 32).unwrap();
+        let ptr = unsafe { align_block(old_raw, layout) };
+        for i in 0..10 {
+            unsafe { ptr.add(i).write(i as u8 + 1) };
+        }
+        let new_ptr = unsafe {
+            realloc_block(
+                ptr,
+                layout,
+                40,
+                |size| {
+                    assert_eq!(size, 40 + 32 + size_of::<usize>());
+                    new_raw
+                },
+                |_, _| panic!("host realloc must not be used"),
+                |raw| freed.set(raw),
+            )
+        };
+        assert_eq!(new_ptr as usize % 32, 0);
+        assert!(new_ptr as usize + 40 <= new_raw as usize + 40 + 32 + size_of::<usize>());
+        assert_eq!(
+            unsafe { core::slice::from_raw_parts(new_ptr, 10) },
+            [1, 2, 3, 4, 5, 6, 7, 8, 9, 10]
+        );
+        assert_eq!(freed.get(), old_raw);
+        let new_layout = Layout::from_size_align(40, 32).unwrap();
+        assert_eq!(unsafe { raw_block(new_ptr, new_layout) }, new_raw);
+
+        // host out of memory, old block is kept
+        freed.set(core::ptr::null_mut());
+        let p = unsafe {
+            realloc_block(
+                ptr,
+                layout,
+                40,
+                |_| core::ptr::null_mut(),
+                |_, _| panic!("host realloc must not be used"),
+                |raw| freed.set(raw),
+            )
+        };
+        assert!(p.is_null());
+        assert!(freed.get().is_null());
+
+        // alignment up to HOST_ALIGN is handled by the host
+        let layout = Layout::from_size_align(10, 8).unwrap();
+        let p = unsafe {
+            realloc_block(
+                old_raw,
+                layout,
+                40,
+                |_| panic!("host malloc must not be used"),
+                |ptr, size| {
+                    assert_eq!((ptr, size), (old_raw, 40));
+                    new_raw
+                },
+                |_| panic!("host free must not be used"),
+            )
+        };
+        assert_eq!(p, new_raw);
+    }
+
+    #[test]
+    fn bump_arena() {
+        const PS: usize = BumpArena::PAGE_SIZE;
+        let mut arena = BumpArena::new();
+        // region starting from address 0x10000
+        let next_page = core::cell::Cell::new(1);
+        let grow = |pages: usize| {
+            let addr = next_page.get() * PS;
+            next_page.set(next_page.get() + pages);
+            Some(addr)
+        };
+
+        let p1 = arena.alloc(Layout::from_size_align(3, 1).unwrap(), grow);
+        assert_eq!(p1 as usize, PS);
+        let p2 = arena.alloc(Layout::from_size_align(8, 8).unwrap(), grow);
+        assert_eq!(p2 as usize, PS + 8);
+        // only the most recent allocation can be resized or released
+        assert!(!arena.resize(p1, 4));
+        assert!(arena.resize(p2, 16));
+        arena.dealloc(p1);
+        assert_eq!(arena.next, PS + 24);
+        arena.dealloc(p2);
+        assert_eq!(arena.next, PS + 8);
+
+        // doesn't fit into current page, continues in the next one
+        let p3 = arena.alloc(Layout::from_size_align(PS, 1).unwrap(), grow);
+        assert_eq!(p3 as usize, PS + 8);
+        assert_eq!(arena.end, 4 * PS);
+        assert!(!arena.resize(p3, 3 * PS));
+        assert!(arena.resize(p3, 2 * PS));
+
+        // new region is not continuation of the current one
+        next_page.set(10);
+        let p4 = arena.alloc(Layout::from_size_align(PS, 16).unwrap(), grow);
+        assert_eq!(p4 as usize, 10 * PS);
+
+        // out of memory
+        let p5 = arena.alloc(Layout::from_size_align(2 * PS, 1).unwrap(), |_| None);
+        assert!(p5.is_null());
+    }
+
+    #[test]
+    #[cfg(feature = "mock-host")]
+    fn host_buf() {
+        use crate::host::mock::store;
+
+        let buf: HostBuf = unsafe { HostBuf::from_raw(store(&[1, 2, 3])) };
+        assert_eq!(&*buf, &[1, 2, 3]);
+
+        let data = [1u32.to_ne_bytes(), 2u32.to_ne_bytes()].concat();
+        let buf: HostBuf<u32> = unsafe { HostBuf::from_raw(store(&data)) };
+        assert_eq!(&*buf, &[1, 2]);
+
+        let buf: HostBuf = unsafe { HostBuf::from_raw(0) };
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "mock-host")]
+    #[should_panic(expected = "use of freed buffer")]
+    fn host_buf_freed() {
+        let ptr = crate::host::mock::store(&[1, 2, 3]);
+        drop(unsafe { HostBuf::<u8>::from_raw(ptr) });
+        byte_array(ptr);
     }
 }
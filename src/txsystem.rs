@@ -6,58 +6,82 @@ use alloc::vec::Vec;
 extern crate alloc;
 
 use crate::{
-    api, decoder,
+    AbDecode, api, cbor,
+    decoder::Value,
     error::{Error, error_code},
-    evaluation_ctx::{self, ABHandle},
-    memory,
+    evaluation_ctx,
+    handle::{self, Bytes, Handle, Object, Proof, PubKeyHash, RawData},
 };
 
 pub mod token;
 
 pub mod money;
 
-#[derive(Default)]
+/// type ID of the native "transaction record" object, see [`evaluation_ctx::create_obj`].
+pub const OBJ_TX_RECORD: u16 = 1;
+/// type ID of the native "transaction proof" object, see [`evaluation_ctx::create_obj`].
+pub const OBJ_TX_PROOF: u16 = 2;
+
+/// unit data was not found
+pub const ERR_UNIT_NOT_FOUND: u8 = 9;
+/// the data is not a (tx record, tx proof) pair
+pub const ERR_INVALID_RECORD_PROOF: u8 = 13;
+/// host failed to create native object out of the data
+pub const ERR_CREATE_OBJ: u8 = 14;
+
+#[derive(Default, AbDecode)]
 pub struct TxOrder {
     // the partitionID, unitID and txType must always be there, the
     // tx order doesn't make sense without them?
     // IOW they are so fundamental that we do not expect them
     // to go away so do not wrap them into an Option? Zero value
     // of the field is enough to detect missing value in this case...?
+    #[ab(tag = 1)]
     pub partition: u32,
+    #[ab(tag = 3)]
     pub typ: u32,
+    #[ab(tag = 2)]
     pub unit_id: Vec<u8>,
+    /// ID of the network (ie mainnet, testnet) the transaction is meant for
+    #[ab(tag = 5)]
+    pub network_id: u32,
+    /// conditions of the unit lock, when the transaction locks the unit
+    #[ab(tag = 6)]
+    pub state_lock: Option<StateLock>,
     // ClientMetadata fields
     /// reference number from transaction client metadata
+    #[ab(tag = 4)]
     pub ref_number: Option<Vec<u8>>,
+    /// round number after which the transaction is not valid anymore
+    #[ab(tag = 7)]
+    pub timeout: Option<u64>,
+    /// max fee the user is willing to pay for the execution of the transaction
+    #[ab(tag = 8)]
+    pub max_fee: Option<u64>,
+    /// ID of the fee credit record the fee is paid from
+    #[ab(tag = 9)]
+    pub fee_credit_record_id: Option<Vec<u8>>,
+    /// proof (ie signature) satisfying the owner predicate of the unit
+    #[ab(tag = 10)]
+    pub auth_proof: Option<Vec<u8>>,
+    /// proof satisfying the owner predicate of the fee credit record, when
+    /// missing the `auth_proof` is used to authorize the fee payment
+    #[ab(tag = 11)]
+    pub fee_proof: Option<Vec<u8>>,
     /// for internal use, mapping the obj to the host variable
-    pub(crate) handle: ABHandle,
+    pub(crate) handle: Handle<handle::TxOrder>,
 }
 
 impl TxOrder {
     pub const TAG_VER: u8 = 1;
 
-    pub fn from_handle(handle: ABHandle) -> Result<TxOrder, Error> {
-        let addr = evaluation_ctx::serialize_obj(handle, Self::TAG_VER);
-        let input = memory::byte_array(addr);
+    pub fn from_handle(handle: Handle<handle::TxOrder>) -> Result<TxOrder, Error> {
+        let input = evaluation_ctx::serialize_obj(handle, Self::TAG_VER);
         let mut txo = Self::from(input).or_else(error_code(12))?;
         txo.handle = handle;
         Ok(txo)
     }
 
-    pub fn from(input: &mut [u8]) -> Result<TxOrder, Error> {
-        let mut txo = TxOrder::default();
-        for fld in decoder::TagValueIter::new(&input) {
-            match fld {
-                (1, v) => txo.partition = v.try_into().or_else(error_code(fld.0))?,
-                (2, v) => txo.unit_id = v.try_into().or_else(error_code(fld.0))?,
-                (3, v) => txo.typ = v.try_into().or_else(error_code(fld.0))?,
-                (4, v) => txo.ref_number = v.try_into().or_else(error_code(fld.0))?,
-                _ => (), // unknown field to us, ignore
-            }
-        }
-        Ok(txo)
-    }
-
     /**
     Attempts to verify the transaction's bearer predicate input (aka OwnerProof)
     as input to P2PKH predicate with given PubKey hash. Returns
@@ -67,7 +91,320 @@ impl TxOrder {
     - Error when the input for the host API is invalid (PubKey hash or tx) or the
       OwnerProof is not valid argument for a P2PKH predicate;
     */
-    pub fn signed_by(&self, pkh: ABHandle) -> api::SignedByResult {
+    pub fn signed_by(&self, pkh: Handle<PubKeyHash>) -> api::SignedByResult {
         api::signed_by_pkh(self.handle, pkh, evaluation_ctx::HANDLE_ARGUMENT)
     }
+
+    /**
+    Evaluates the predicate (ie owner predicate of some unit) against the
+    transaction with the predicate's argument `proof`, see [`api::satisfies`].
+    */
+    pub fn satisfies(&self, predicate: &[u8], proof: Handle<Proof>) -> api::EvalResult {
+        api::satisfies(self.handle, predicate, proof)
+    }
+
+    /// Returns handle of the variable containing the `auth_proof`
+    /// of the transaction (when present).
+    pub fn auth_proof_handle(&self) -> Option<Handle<Proof>> {
+        self.auth_proof
+            .as_ref()
+            .map(|v| evaluation_ctx::add_var(v.clone()).proof())
+    }
+
+    /// Returns handle of the variable containing the `fee_proof`
+    /// of the transaction (when present).
+    pub fn fee_proof_handle(&self) -> Option<Handle<Proof>> {
+        self.fee_proof
+            .as_ref()
+            .map(|v| evaluation_ctx::add_var(v.clone()).proof())
+    }
+}
+
+/// tx order embedded into tx record is encoded as tag-value encoded byte slice.
+impl TryFrom<Value> for TxOrder {
+    type Error = Error;
+
+    fn try_from(v: Value) -> Result<Self, Self::Error> {
+        TxOrder::from(Vec::<u8>::try_from(v)?)
+    }
+}
+
+/**
+State lock of the transaction order.
+
+When the transaction has state lock the unit is locked and the transaction is
+executed later when the execution predicate is satisfied (or rolled back when
+the rollback predicate is satisfied).
+*/
+#[derive(Default, AbDecode)]
+pub struct StateLock {
+    #[ab(tag = 1)]
+    pub execution_predicate: Option<Vec<u8>>,
+    #[ab(tag = 2)]
+    pub rollback_predicate: Option<Vec<u8>>,
+}
+
+/// state lock is encoded as tag-value encoded byte slice.
+impl TryFrom<Value> for StateLock {
+    type Error = Error;
+
+    fn try_from(v: Value) -> Result<Self, Self::Error> {
+        StateLock::from(Vec::<u8>::try_from(v)?)
+    }
+}
+
+/**
+Record of an executed transaction.
+
+The `tx_order` of the record is not backed by host side object so methods which
+require one (ie [`TxOrder::signed_by`]) fail.
+*/
+#[derive(Default, AbDecode)]
+pub struct TxRecord {
+    #[ab(tag = 1)]
+    pub tx_order: TxOrder,
+    #[ab(tag = 2)]
+    pub server_metadata: Option<ServerMetadata>,
+    /// for internal use, mapping the obj to the host variable
+    pub(crate) handle: Handle<handle::TxRecord>,
+}
+
+impl TxRecord {
+    pub const TAG_VER: u8 = 1;
+
+    /// Creates tx record out of host variable containing CBOR encoded tx record.
+    pub fn from_handle(handle: Handle<Bytes>) -> Result<TxRecord, Error> {
+        let obj = create_obj(handle)?;
+        let mut txr = Self::from(evaluation_ctx::serialize_obj(obj, Self::TAG_VER))?;
+        txr.handle = obj;
+        Ok(txr)
+    }
+}
+
+/// Metadata added to the transaction by the validator executing it.
+#[derive(Default, AbDecode)]
+pub struct ServerMetadata {
+    /// fee actually charged for the execution of the transaction
+    #[ab(tag = 1)]
+    pub actual_fee: Option<u64>,
+    #[ab(tag = 2)]
+    pub success_indicator: Option<u32>,
+    /// IDs of the units modified by the transaction
+    #[ab(tag = 3)]
+    pub target_units: Option<Vec<Vec<u8>>>,
+}
+
+impl ServerMetadata {
+    pub const TX_SUCCESSFUL: u32 = 1;
+
+    /// was the transaction executed successfully
+    pub fn is_success(&self) -> bool {
+        self.success_indicator == Some(Self::TX_SUCCESSFUL)
+    }
+}
+
+/// server metadata is encoded as tag-value encoded byte slice.
+impl TryFrom<Value> for ServerMetadata {
+    type Error = Error;
+
+    fn try_from(v: Value) -> Result<Self, Self::Error> {
+        ServerMetadata::from(Vec::<u8>::try_from(v)?)
+    }
+}
+
+/**
+Proof of the inclusion of the transaction into a block.
+*/
+#[derive(Default, AbDecode)]
+pub struct TxProof {
+    #[ab(tag = 1)]
+    pub block_header_hash: Option<Vec<u8>>,
+    /// round number of the unicity certificate of the block
+    #[ab(tag = 2)]
+    pub uc_round: Option<u64>,
+    /// for internal use, mapping the obj to the host variable
+    pub(crate) handle: Handle<handle::TxProof>,
+}
+
+impl TxProof {
+    pub const TAG_VER: u8 = 1;
+
+    /// Creates tx proof out of host variable containing CBOR encoded tx proof.
+    pub fn from_handle(handle: Handle<Bytes>) -> Result<TxProof, Error> {
+        let obj = create_obj(handle)?;
+        let mut proof = Self::from(evaluation_ctx::serialize_obj(obj, Self::TAG_VER))?;
+        proof.handle = obj;
+        Ok(proof)
+    }
+}
+
+/**
+Transaction record together with its proof, ie item of the array saved by
+the wallet's "save proof" flag.
+*/
+pub struct TxRecordProof {
+    pub record: TxRecord,
+    pub proof: TxProof,
+}
+
+impl TxRecordProof {
+    /// Creates the pair out of host variable containing CBOR array `[record, proof]`.
+    pub fn from_handle(handle: Handle<Bytes>) -> Result<TxRecordProof, Error> {
+        let &[record, proof] = &*cbor::chunks(handle) else {
+            return Err(Error::new(ERR_INVALID_RECORD_PROOF));
+        };
+        Ok(TxRecordProof {
+            record: TxRecord::from_handle(record).or_else(error_code(1))?,
+            proof: TxProof::from_handle(proof).or_else(error_code(2))?,
+        })
+    }
+
+    /// Asks host to verify that the proof is valid for the record.
+    pub fn verify(&self) -> Result<bool, Error> {
+        api::verify_tx_proof(self.proof.handle, self.record.handle)
+    }
+}
+
+/**
+Returns record-proof pairs out of host variable containing CBOR array of
+them (ie the argument of [`api::amount_transferred`]).
+*/
+pub fn tx_record_proofs<T: RawData>(handle: Handle<T>) -> Result<Vec<TxRecordProof>, Error> {
+    cbor::chunks(handle)
+        .iter()
+        .map(|h| TxRecordProof::from_handle(*h))
+        .collect()
+}
+
+fn create_obj<T: Object>(handle: Handle<Bytes>) -> Result<Handle<T>, Error> {
+    match evaluation_ctx::create_obj(handle) {
+        h if h == Handle::default() => Err(Error::new(ERR_CREATE_OBJ)),
+        h => Ok(h),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn txorder_all_fields() {
+        let data = [
+            0x1, 0x3, 0x2, 0x0, 0x0, 0x0, 0x2, 0x1, 0x3, 0x0, 0x0, 0x0, 0x1, 0x2, 0x3, 0x3, 0x3,
+            0x4, 0x0, 0x0, 0x0, 0x5, 0x3, 0x3, 0x0, 0x0, 0x0, 0x6, 0x1, 0x14, 0x0, 0x0, 0x0, 0x1,
+            0x1, 0x4, 0x0, 0x0, 0x0, 0x83, 0x0, 0x41, 0x1, 0x2, 0x1, 0x4, 0x0, 0x0, 0x0, 0x83, 0x0,
+            0x41, 0x2, 0x7, 0x2, 0xe8, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x8, 0x2, 0xa, 0x0, 0x0,
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x9, 0x1, 0x2, 0x0, 0x0, 0x0, 0xf, 0xc, 0xa, 0x1, 0x2, 0x0,
+            0x0, 0x0, 0xa, 0xb, 0xb, 0x1, 0x1, 0x0, 0x0, 0x0, 0xc,
+        ];
+        let txo = TxOrder::from(data).unwrap();
+        assert_eq!(txo.partition, 2);
+        assert_eq!(txo.unit_id, [1, 2, 3]);
+        assert_eq!(txo.typ, 4);
+        assert_eq!(txo.network_id, 3);
+        assert_eq!(txo.ref_number, None);
+        assert_eq!(txo.timeout, Some(1000));
+        assert_eq!(txo.max_fee, Some(10));
+        assert_eq!(txo.fee_credit_record_id.unwrap(), [0xf, 0xc]);
+        assert_eq!(txo.auth_proof.unwrap(), [0xa, 0xb]);
+        assert_eq!(txo.fee_proof.unwrap(), [0xc]);
+        let sl = txo.state_lock.unwrap();
+        assert_eq!(sl.execution_predicate.unwrap(), [0x83, 0x0, 0x41, 0x1]);
+        assert_eq!(sl.rollback_predicate.unwrap(), [0x83, 0x0, 0x41, 0x2]);
+    }
+
+    #[test]
+    #[cfg(feature = "mock-host")]
+    fn txorder_from_host() {
+        use crate::host::mock::MockHost;
+
+        MockHost::new()
+            .tx_order(1, 2, &[3])
+            .network_id(2)
+            .client_metadata(100, 5, &[7, 8])
+            .proofs(&[0xa], None)
+            .install();
+        let txo = evaluation_ctx::tx_order().unwrap();
+        assert_eq!(txo.network_id, 2);
+        assert_eq!(txo.timeout, Some(100));
+        assert_eq!(txo.max_fee, Some(5));
+        assert_eq!(txo.fee_credit_record_id.as_deref(), Some(&[7u8, 8][..]));
+        assert!(txo.state_lock.is_none());
+        assert!(txo.fee_proof_handle().is_none());
+        let h = txo.auth_proof_handle().unwrap();
+        assert_eq!(evaluation_ctx::var_data(h).unwrap(), [0xa]);
+    }
+
+    #[test]
+    #[cfg(feature = "mock-host")]
+    fn tx_record_proofs_from_host() {
+        use crate::{
+            cbor::Item,
+            host::mock::{Encoder, MockHost},
+        };
+        use alloc::vec;
+
+        let record = |n: u64| Item::Array(vec![Item::Uint(n), Item::Bytes(vec![1])]);
+        let proof = |n: u64| Item::Array(vec![Item::Uint(n)]);
+        let pair = |n: u64| Item::Array(vec![record(n), proof(n)]);
+        let proofs = Item::Array(vec![pair(1), pair(2)]).encode();
+
+        let txo = Encoder::new()
+            .u32(1, 2)
+            .bytes(2, &[8, 9])
+            .u32(3, 5)
+            .finish();
+        let sm = Encoder::new()
+            .u64(1, 3)
+            .u32(2, 1)
+            .value(3, &Value::Array(vec![Value::Bytes(vec![8, 9])]))
+            .finish();
+        let rec_data = Encoder::new().bytes(1, &txo).bytes(2, &sm).finish();
+        let proof_data = |round| Encoder::new().bytes(1, &[0xbb]).u64(2, round).finish();
+
+        MockHost::new()
+            .argument(&proofs)
+            .object(OBJ_TX_RECORD, &record(1).encode(), rec_data.clone())
+            .object(OBJ_TX_RECORD, &record(2).encode(), rec_data)
+            .object(OBJ_TX_PROOF, &proof(1).encode(), proof_data(10))
+            .object(OBJ_TX_PROOF, &proof(2).encode(), proof_data(20))
+            .valid_tx_proof(&record(1).encode(), &proof(1).encode())
+            .install();
+
+        let items = tx_record_proofs(evaluation_ctx::HANDLE_ARGUMENT).unwrap();
+        assert_eq!(items.len(), 2);
+        let txr = &items[0].record;
+        assert_eq!(txr.tx_order.partition, 2);
+        assert_eq!(txr.tx_order.unit_id, [8, 9]);
+        assert_eq!(txr.tx_order.typ, 5);
+        let sm = txr.server_metadata.as_ref().unwrap();
+        assert_eq!(sm.actual_fee, Some(3));
+        assert!(sm.is_success());
+        assert_eq!(sm.target_units, Some(vec![vec![8, 9]]));
+        assert_eq!(items[0].proof.uc_round, Some(10));
+        assert_eq!(items[1].proof.uc_round, Some(20));
+
+        assert!(items[0].verify().unwrap());
+        assert!(!items[1].verify().unwrap());
+
+        // argument is not array of pairs
+        let triple = Item::Array(vec![Item::Uint(1), Item::Uint(2), Item::Uint(3)]);
+        MockHost::new()
+            .argument(&Item::Array(vec![triple]).encode())
+            .install();
+        assert_eq!(
+            tx_record_proofs(evaluation_ctx::HANDLE_ARGUMENT)
+                .err()
+                .unwrap()
+                .code(),
+            ERR_INVALID_RECORD_PROOF as u64
+        );
+
+        // objects not registered, failure to load the record
+        MockHost::new().argument(&proofs).install();
+        let err = tx_record_proofs(evaluation_ctx::HANDLE_ARGUMENT)
+            .err()
+            .unwrap();
+        assert_eq!(err.code() & 0xff, 1);
+    }
 }
@@ -1,20 +1,38 @@
 extern crate alloc;
-#[cfg(feature = "ft-create-type")]
+#[cfg(any(feature = "ft-create-type", feature = "ft-type-data"))]
 use alloc::string::String;
 #[cfg(any(
     feature = "ft-create-type",
     feature = "ft-mint-token",
     feature = "ft-transfer",
+    feature = "ft-split",
+    feature = "ft-burn",
+    feature = "ft-join",
+    feature = "ft-token-data",
+    feature = "ft-type-data",
 ))]
 use alloc::vec::Vec;
 
+#[cfg(feature = "ft-join")]
+use crate::handle::{Bytes, Handle};
+#[cfg(feature = "ft-type-data")]
+use crate::txsystem::token::Icon;
 #[cfg(any(
     feature = "ft-create-type",
     feature = "ft-mint-token",
     feature = "ft-transfer",
+    feature = "ft-split",
+    feature = "ft-burn",
+    feature = "ft-join",
+    feature = "ft-lock",
+    feature = "ft-unlock",
+    feature = "ft-token-data",
+    feature = "ft-type-data",
 ))]
-use crate::{decoder::Decoder, evaluation_ctx};
+use crate::{AbDecode, evaluation_ctx};
 
+#[cfg(any(feature = "ft-token-data", feature = "ft-type-data"))]
+use crate::txsystem::ERR_UNIT_NOT_FOUND;
 use crate::{error::Error, txsystem::TxOrder};
 
 /*
@@ -28,12 +46,26 @@ use crate::{error::Error, txsystem::TxOrder};
     TransactionTypeJoinFT      uint16 = 11
 */
 
+// TODO: the tags of the FT structs are not verified against the host's encoding,
+// unlike nft there are no tests generated by the Go backend (ie Test_generate_NFT_DecodeTests)
+// for them yet - generate ft_test.rs from the backend when it's supported there.
+
 #[cfg(feature = "ft-create-type")]
 const PAYLOAD_TYPE_FT_CREATE_TYPE: u32 = 1;
 #[cfg(feature = "ft-mint-token")]
 const PAYLOAD_TYPE_FT_MINT: u32 = 3;
 #[cfg(feature = "ft-transfer")]
 const PAYLOAD_TYPE_FT_TRANSFER: u32 = 5;
+#[cfg(feature = "ft-lock")]
+const PAYLOAD_TYPE_FT_LOCK: u32 = 7;
+#[cfg(feature = "ft-unlock")]
+const PAYLOAD_TYPE_FT_UNLOCK: u32 = 8;
+#[cfg(feature = "ft-split")]
+const PAYLOAD_TYPE_FT_SPLIT: u32 = 9;
+#[cfg(feature = "ft-burn")]
+const PAYLOAD_TYPE_FT_BURN: u32 = 10;
+#[cfg(feature = "ft-join")]
+const PAYLOAD_TYPE_FT_JOIN: u32 = 11;
 
 /// different fungible token tx attributes
 pub enum TxKind {
@@ -43,82 +75,383 @@ pub enum TxKind {
     Mint(Mint),
     #[cfg(feature = "ft-transfer")]
     Transfer(Transfer),
+    #[cfg(feature = "ft-lock")]
+    Lock(Lock),
+    #[cfg(feature = "ft-unlock")]
+    Unlock(Unlock),
+    #[cfg(feature = "ft-split")]
+    Split(Split),
+    #[cfg(feature = "ft-burn")]
+    Burn(Burn),
+    #[cfg(feature = "ft-join")]
+    Join(Join),
 }
 
 pub fn tx_attributes(txo: &TxOrder) -> Result<TxKind, Error> {
-    #[cfg(any(
-        feature = "ft-create-type",
-        feature = "ft-mint-token",
-        feature = "ft-transfer",
-    ))]
-    let data = evaluation_ctx::tx_attributes(txo.handle, 1);
     match txo.typ {
         #[cfg(feature = "ft-create-type")]
-        PAYLOAD_TYPE_FT_CREATE_TYPE => Ok(TxKind::CreateType(CreateType::from(data)?)),
+        PAYLOAD_TYPE_FT_CREATE_TYPE => Ok(TxKind::CreateType(CreateType::load(txo)?)),
         #[cfg(feature = "ft-mint-token")]
-        PAYLOAD_TYPE_FT_MINT => Ok(TxKind::Mint(Mint::from(data)?)),
+        PAYLOAD_TYPE_FT_MINT => Ok(TxKind::Mint(Mint::load(txo)?)),
         #[cfg(feature = "ft-transfer")]
-        PAYLOAD_TYPE_FT_TRANSFER => Ok(TxKind::Transfer(Transfer::from(data)?)),
+        PAYLOAD_TYPE_FT_TRANSFER => Ok(TxKind::Transfer(Transfer::load(txo)?)),
+        #[cfg(feature = "ft-lock")]
+        PAYLOAD_TYPE_FT_LOCK => Ok(TxKind::Lock(Lock::load(txo)?)),
+        #[cfg(feature = "ft-unlock")]
+        PAYLOAD_TYPE_FT_UNLOCK => Ok(TxKind::Unlock(Unlock::load(txo)?)),
+        #[cfg(feature = "ft-split")]
+        PAYLOAD_TYPE_FT_SPLIT => Ok(TxKind::Split(Split::load(txo)?)),
+        #[cfg(feature = "ft-burn")]
+        PAYLOAD_TYPE_FT_BURN => Ok(TxKind::Burn(Burn::load(txo)?)),
+        #[cfg(feature = "ft-join")]
+        PAYLOAD_TYPE_FT_JOIN => Ok(TxKind::Join(Join::load(txo)?)),
         _ => Err(Error::new(1)),
     }
 }
 
+/**
+Attributes of the "define fungible token type" transaction.
+*/
 #[cfg(feature = "ft-create-type")]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+#[derive(Default, AbDecode)]
 pub struct CreateType {
-    pub symbol: String,
-    pub name: String,
+    #[ab(tag = 1)]
+    pub symbol: Option<String>,
+    #[ab(tag = 2)]
+    pub name: Option<String>,
     /// parent type of the type, empty for root type
-    pub type_id: Vec<u8>,
-    pub decimals: u32,
+    /// NB! use Some(empty vec) to distinguish between missing
+    /// value and empty value!
+    #[ab(tag = 3)]
+    pub type_id: Option<Vec<u8>>,
+    #[ab(tag = 4)]
+    pub decimals: Option<u32>,
 }
 
 #[cfg(feature = "ft-create-type")]
 impl CreateType {
-    pub fn from(input: Vec<u8>) -> Result<Self, Error> {
-        let mut p = Decoder::new(&input);
-        Ok(Self {
-            symbol: p.string(),
-            name: p.string(),
-            type_id: p.bytes(),
-            decimals: p.uint32(),
-        })
+    pub const TAG_VER: u8 = 1;
+
+    pub fn load(txo: &TxOrder) -> Result<Self, Error> {
+        let data = evaluation_ctx::tx_attributes(txo.handle, Self::TAG_VER);
+        Self::from(data)
     }
 }
 
+/**
+Attributes of the "mint fungible token" transaction.
+*/
 #[cfg(feature = "ft-mint-token")]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+#[derive(Default, AbDecode)]
 pub struct Mint {
-    pub type_id: Vec<u8>,
-    pub value: u64,
-    pub nonce: Vec<u8>,
+    #[ab(tag = 1)]
+    pub type_id: Option<Vec<u8>>,
+    #[ab(tag = 2)]
+    pub value: Option<u64>,
+    #[ab(tag = 3)]
+    pub nonce: Option<u64>,
 }
 
 #[cfg(feature = "ft-mint-token")]
 impl Mint {
-    pub fn from(input: Vec<u8>) -> Result<Self, Error> {
-        let mut p = Decoder::new(&input);
-        Ok(Self {
-            type_id: p.bytes(),
-            value: p.uint64(),
-            nonce: p.bytes(),
-        })
+    pub const TAG_VER: u8 = 1;
+
+    pub fn load(txo: &TxOrder) -> Result<Self, Error> {
+        let data = evaluation_ctx::tx_attributes(txo.handle, Self::TAG_VER);
+        Self::from(data)
     }
 }
 
+/**
+Attributes of the "transfer fungible token" transaction.
+*/
 #[cfg(feature = "ft-transfer")]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+#[derive(Default, AbDecode)]
 pub struct Transfer {
-    pub value: u64,
-    pub type_id: Vec<u8>,
-    pub counter: u64,
+    #[ab(tag = 1)]
+    pub type_id: Option<Vec<u8>>,
+    #[ab(tag = 2)]
+    pub counter: Option<u64>,
+    #[ab(tag = 3)]
+    pub value: Option<u64>,
 }
 
 #[cfg(feature = "ft-transfer")]
 impl Transfer {
-    pub fn from(input: Vec<u8>) -> Result<Self, Error> {
-        let mut p = Decoder::new(&input);
-        Ok(Self {
-            value: p.uint64(),
-            type_id: p.bytes(),
-            counter: p.uint64(),
-        })
+    pub const TAG_VER: u8 = 1;
+
+    pub fn load(txo: &TxOrder) -> Result<Self, Error> {
+        let data = evaluation_ctx::tx_attributes(txo.handle, Self::TAG_VER);
+        Self::from(data)
+    }
+}
+
+/**
+Attributes of the "split fungible token" transaction.
+*/
+#[cfg(feature = "ft-split")]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+#[derive(Default, AbDecode)]
+pub struct Split {
+    #[ab(tag = 1)]
+    pub type_id: Option<Vec<u8>>,
+    /// value of the new token
+    #[ab(tag = 2)]
+    pub target_value: Option<u64>,
+    /// owner predicate of the new token
+    #[ab(tag = 3)]
+    pub new_owner_predicate: Option<Vec<u8>>,
+    /// value left to the token being split
+    #[ab(tag = 4)]
+    pub remaining_value: Option<u64>,
+    #[ab(tag = 5)]
+    pub counter: Option<u64>,
+}
+
+#[cfg(feature = "ft-split")]
+impl Split {
+    pub const TAG_VER: u8 = 1;
+
+    pub fn load(txo: &TxOrder) -> Result<Self, Error> {
+        let data = evaluation_ctx::tx_attributes(txo.handle, Self::TAG_VER);
+        Self::from(data)
+    }
+}
+
+/**
+Attributes of the "burn fungible token" transaction.
+*/
+#[cfg(feature = "ft-burn")]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+#[derive(Default, AbDecode)]
+pub struct Burn {
+    #[ab(tag = 1)]
+    pub type_id: Option<Vec<u8>>,
+    /// value of the token burned
+    #[ab(tag = 2)]
+    pub value: Option<u64>,
+    /// token the burned value is going to be joined into
+    #[ab(tag = 3)]
+    pub target_token_id: Option<Vec<u8>>,
+    #[ab(tag = 4)]
+    pub target_token_counter: Option<u64>,
+    #[ab(tag = 5)]
+    pub counter: Option<u64>,
+}
+
+#[cfg(feature = "ft-burn")]
+impl Burn {
+    pub const TAG_VER: u8 = 1;
+
+    pub fn load(txo: &TxOrder) -> Result<Self, Error> {
+        let data = evaluation_ctx::tx_attributes(txo.handle, Self::TAG_VER);
+        Self::from(data)
+    }
+}
+
+/**
+Attributes of the "join fungible tokens" transaction.
+
+Burn transactions and their proofs are CBOR encoded transaction records and
+proofs, use [`burn_tx_handles`](Join::burn_tx_handles) and
+[`proof_handles`](Join::proof_handles) to pass them to the host APIs.
+*/
+#[cfg(feature = "ft-join")]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+#[derive(Default, AbDecode)]
+pub struct Join {
+    #[ab(tag = 1)]
+    pub burn_transactions: Option<Vec<Vec<u8>>>,
+    #[ab(tag = 2)]
+    pub proofs: Option<Vec<Vec<u8>>>,
+    #[ab(tag = 3)]
+    pub counter: Option<u64>,
+}
+
+#[cfg(feature = "ft-join")]
+impl Join {
+    pub const TAG_VER: u8 = 1;
+
+    pub fn load(txo: &TxOrder) -> Result<Self, Error> {
+        let data = evaluation_ctx::tx_attributes(txo.handle, Self::TAG_VER);
+        Self::from(data)
+    }
+
+    /// returns handles of host variables of the burn transaction records.
+    pub fn burn_tx_handles(&self) -> Vec<Handle<Bytes>> {
+        add_vars(&self.burn_transactions)
+    }
+
+    /// returns handles of host variables of the burn transaction proofs.
+    pub fn proof_handles(&self) -> Vec<Handle<Bytes>> {
+        add_vars(&self.proofs)
+    }
+}
+
+#[cfg(feature = "ft-join")]
+fn add_vars(items: &Option<Vec<Vec<u8>>>) -> Vec<Handle<Bytes>> {
+    items
+        .iter()
+        .flatten()
+        .map(|v| evaluation_ctx::add_var(v.clone()))
+        .collect()
+}
+
+/**
+Attributes of the "lock token" transaction.
+*/
+#[cfg(feature = "ft-lock")]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+#[derive(Default, AbDecode)]
+pub struct Lock {
+    /// non-zero value, the reason of the locking
+    #[ab(tag = 1)]
+    pub lock_status: Option<u64>,
+    #[ab(tag = 2)]
+    pub counter: Option<u64>,
+}
+
+#[cfg(feature = "ft-lock")]
+impl Lock {
+    pub const TAG_VER: u8 = 1;
+
+    pub fn load(txo: &TxOrder) -> Result<Self, Error> {
+        let data = evaluation_ctx::tx_attributes(txo.handle, Self::TAG_VER);
+        Self::from(data)
+    }
+}
+
+/**
+Attributes of the "unlock token" transaction.
+*/
+#[cfg(feature = "ft-unlock")]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+#[derive(Default, AbDecode)]
+pub struct Unlock {
+    #[ab(tag = 1)]
+    pub counter: Option<u64>,
+}
+
+#[cfg(feature = "ft-unlock")]
+impl Unlock {
+    pub const TAG_VER: u8 = 1;
+
+    pub fn load(txo: &TxOrder) -> Result<Self, Error> {
+        let data = evaluation_ctx::tx_attributes(txo.handle, Self::TAG_VER);
+        Self::from(data)
+    }
+}
+
+/**
+Data of a fungible token unit.
+*/
+#[cfg(feature = "ft-token-data")]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+#[derive(Default, AbDecode)]
+pub struct TokenData {
+    #[ab(tag = 1)]
+    pub type_id: Option<Vec<u8>>,
+    /// value (balance) of the token
+    #[ab(tag = 2)]
+    pub value: Option<u64>,
+    #[ab(tag = 3)]
+    pub counter: Option<u64>,
+    /// locked status of the token, non-zero value means locked
+    #[ab(tag = 4)]
+    pub locked: Option<u64>,
+    /// round number until which the token can't be joined into
+    #[ab(tag = 5)]
+    pub timeout: Option<u64>,
+}
+
+#[cfg(feature = "ft-token-data")]
+impl TokenData {
+    pub const TAG_VER: u8 = 1;
+
+    /// loads the data of the token, [`ERR_UNIT_NOT_FOUND`] is returned when the unit has no data.
+    pub fn load(unit_id: &[u8], committed: bool) -> Result<Self, Error> {
+        let ud = evaluation_ctx::unit_data(unit_id, committed, Self::TAG_VER);
+        if ud.is_empty() {
+            return Err(Error::new(ERR_UNIT_NOT_FOUND));
+        }
+        Self::from(ud)
+    }
+}
+
+/**
+Data of a fungible token type.
+*/
+#[cfg(feature = "ft-type-data")]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+#[derive(Default, AbDecode)]
+pub struct TypeData {
+    /// parent type of the type, empty means "root type"
+    #[ab(tag = 1)]
+    pub parent_id: Option<Vec<u8>>,
+    #[ab(tag = 2)]
+    pub symbol: Option<String>,
+    #[ab(tag = 3)]
+    pub name: Option<String>,
+    /// number of decimal places of the token value
+    #[ab(tag = 4)]
+    pub decimals: Option<u32>,
+    #[ab(tag = 5)]
+    pub icon: Option<Icon>,
+}
+
+#[cfg(feature = "ft-type-data")]
+impl TypeData {
+    pub const TAG_VER: u8 = 1;
+
+    /// loads the data of the token type, [`ERR_UNIT_NOT_FOUND`] is returned when the unit has no data.
+    pub fn load(unit_id: &[u8], committed: bool) -> Result<Self, Error> {
+        let ud = evaluation_ctx::unit_data(unit_id, committed, Self::TAG_VER);
+        if ud.is_empty() {
+            return Err(Error::new(ERR_UNIT_NOT_FOUND));
+        }
+        Self::from(ud)
+    }
+}
+
+// decode tests with hand-built tag-value fixtures
+#[cfg(test)]
+mod ft_test;
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    #[cfg(all(
+        feature = "mock-host",
+        feature = "ft-token-data",
+        feature = "ft-type-data"
+    ))]
+    fn load_missing_unit() {
+        use crate::host::mock::{Encoder, MockHost};
+        use alloc::vec;
+
+        MockHost::new()
+            .unit_data(&[1], Encoder::new().bytes(1, &[2]).finish())
+            .unit_data(&[2], Encoder::new().string(2, "FT").finish())
+            .install();
+
+        assert_eq!(TokenData::load(&[1], true).unwrap().type_id, Some(vec![2]));
+        assert_eq!(
+            TypeData::load(&[2], true).unwrap().symbol.as_deref(),
+            Some("FT")
+        );
+        assert_eq!(
+            TokenData::load(&[3], true).unwrap_err().code(),
+            ERR_UNIT_NOT_FOUND as u64
+        );
+        assert_eq!(
+            TypeData::load(&[3], true).unwrap_err().code(),
+            ERR_UNIT_NOT_FOUND as u64
+        );
     }
 }
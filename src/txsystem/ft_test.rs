@@ -0,0 +1,296 @@
+// decode tests of the fungible token tx attributes and unit data. The data is
+// hand-built tag-value encoding following the tags declared in ft.rs, it is not
+// produced by the host, the comment of each case lists the encoded fields.
+
+extern crate alloc;
+use super::*;
+#[cfg(feature = "ft-type-data")]
+use crate::txsystem::token::Icon;
+#[allow(unused_imports)]
+use alloc::{string::ToString, vec};
+
+#[test]
+#[cfg(feature = "ft-create-type")]
+fn create_type() {
+    // symbol (1): "FT", name (2): "fungible", decimals (4): 8
+    let data = vec![
+        0x1, 0x4, 0x2, 0x0, 0x0, 0x0, 0x46, 0x54, 0x2, 0x4, 0x8, 0x0, 0x0, 0x0, 0x66, 0x75, 0x6e,
+        0x67, 0x69, 0x62, 0x6c, 0x65, 0x4, 0x3, 0x8, 0x0, 0x0, 0x0,
+    ];
+    assert_eq!(
+        CreateType::from(data).unwrap(),
+        CreateType {
+            symbol: Some("FT".to_string()),
+            name: Some("fungible".to_string()),
+            type_id: None,
+            decimals: Some(8),
+        }
+    );
+    // symbol (1): "EUR", type_id (3): [0x2, 0x0, 0x3], decimals (4): 2
+    let data = vec![
+        0x1, 0x4, 0x3, 0x0, 0x0, 0x0, 0x45, 0x55, 0x52, 0x3, 0x1, 0x3, 0x0, 0x0, 0x0, 0x2, 0x0,
+        0x3, 0x4, 0x3, 0x2, 0x0, 0x0, 0x0,
+    ];
+    assert_eq!(
+        CreateType::from(data).unwrap(),
+        CreateType {
+            symbol: Some("EUR".to_string()),
+            name: None,
+            type_id: Some(vec![0x2, 0x0, 0x3]),
+            decimals: Some(2),
+        }
+    );
+}
+
+#[test]
+#[cfg(feature = "ft-mint-token")]
+fn mint() {
+    // type_id (1): [0x2, 0x0, 0x3], value (2): 1000, nonce (3): 0
+    let data = vec![
+        0x1, 0x1, 0x3, 0x0, 0x0, 0x0, 0x2, 0x0, 0x3, 0x2, 0x2, 0xe8, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x3, 0x2, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    ];
+    assert_eq!(
+        Mint::from(data).unwrap(),
+        Mint {
+            type_id: Some(vec![0x2, 0x0, 0x3]),
+            value: Some(1000),
+            nonce: Some(0),
+        }
+    );
+    // type_id (1): [0xff], value (2): 18446744073709551615, nonce (3): 42
+    let data = vec![
+        0x1, 0x1, 0x1, 0x0, 0x0, 0x0, 0xff, 0x2, 0x2, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0x3, 0x2, 0x2a, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    ];
+    assert_eq!(
+        Mint::from(data).unwrap(),
+        Mint {
+            type_id: Some(vec![0xff]),
+            value: Some(18446744073709551615),
+            nonce: Some(42),
+        }
+    );
+}
+
+#[test]
+#[cfg(feature = "ft-transfer")]
+fn transfer() {
+    // type_id (1): [0x2, 0x0, 0x3], counter (2): 4, value (3): 100
+    let data = vec![
+        0x1, 0x1, 0x3, 0x0, 0x0, 0x0, 0x2, 0x0, 0x3, 0x2, 0x2, 0x4, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x3, 0x2, 0x64, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    ];
+    assert_eq!(
+        Transfer::from(data).unwrap(),
+        Transfer {
+            type_id: Some(vec![0x2, 0x0, 0x3]),
+            counter: Some(4),
+            value: Some(100),
+        }
+    );
+    // counter (2): 0, value (3): 0
+    let data = vec![
+        0x2, 0x2, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x3, 0x2, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0,
+    ];
+    assert_eq!(
+        Transfer::from(data).unwrap(),
+        Transfer {
+            type_id: None,
+            counter: Some(0),
+            value: Some(0),
+        }
+    );
+}
+
+#[test]
+#[cfg(feature = "ft-split")]
+fn split() {
+    // type_id (1): [0x2, 0x0, 0x1], target_value (2): 100, new_owner_predicate (3): [0x83, 0x0, 0x41, 0x1], remaining_value (4): 900, counter (5): 3
+    let data = vec![
+        0x1, 0x1, 0x3, 0x0, 0x0, 0x0, 0x2, 0x0, 0x1, 0x2, 0x2, 0x64, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x3, 0x1, 0x4, 0x0, 0x0, 0x0, 0x83, 0x0, 0x41, 0x1, 0x4, 0x2, 0x84, 0x3, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x5, 0x2, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    ];
+    assert_eq!(
+        Split::from(data).unwrap(),
+        Split {
+            type_id: Some(vec![0x2, 0x0, 0x1]),
+            target_value: Some(100),
+            new_owner_predicate: Some(vec![0x83, 0x0, 0x41, 0x1]),
+            remaining_value: Some(900),
+            counter: Some(3),
+        }
+    );
+    // target_value (2): 1, remaining_value (4): 0, counter (5): 0
+    let data = vec![
+        0x2, 0x2, 0x1, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x4, 0x2, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x5, 0x2, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    ];
+    assert_eq!(
+        Split::from(data).unwrap(),
+        Split {
+            type_id: None,
+            target_value: Some(1),
+            new_owner_predicate: None,
+            remaining_value: Some(0),
+            counter: Some(0),
+        }
+    );
+}
+
+#[test]
+#[cfg(feature = "ft-burn")]
+fn burn() {
+    // type_id (1): [0x2, 0x0, 0x1], value (2): 1000, target_token_id (3): [0x2, 0x0, 0x7], target_token_counter (4): 5, counter (5): 1
+    let data = vec![
+        0x1, 0x1, 0x3, 0x0, 0x0, 0x0, 0x2, 0x0, 0x1, 0x2, 0x2, 0xe8, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x3, 0x1, 0x3, 0x0, 0x0, 0x0, 0x2, 0x0, 0x7, 0x4, 0x2, 0x5, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x5, 0x2, 0x1, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    ];
+    assert_eq!(
+        Burn::from(data).unwrap(),
+        Burn {
+            type_id: Some(vec![0x2, 0x0, 0x1]),
+            value: Some(1000),
+            target_token_id: Some(vec![0x2, 0x0, 0x7]),
+            target_token_counter: Some(5),
+            counter: Some(1),
+        }
+    );
+}
+
+#[test]
+#[cfg(feature = "ft-join")]
+fn join() {
+    // burn_transactions (1): [], proofs (2): [], counter (3): 2
+    let data = vec![
+        0x1, 0x5, 0x0, 0x0, 0x0, 0x0, 0x2, 0x5, 0x0, 0x0, 0x0, 0x0, 0x3, 0x2, 0x2, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0,
+    ];
+    assert_eq!(
+        Join::from(data).unwrap(),
+        Join {
+            burn_transactions: Some(vec![]),
+            proofs: Some(vec![]),
+            counter: Some(2),
+        }
+    );
+    // burn_transactions (1): [h'820102'], proofs (2): [h'8103'], counter (3): 7
+    let data = vec![
+        0x1, 0x5, 0x1, 0x0, 0x0, 0x0, 0x1, 0x3, 0x0, 0x0, 0x0, 0x82, 0x1, 0x2, 0x2, 0x5, 0x1, 0x0,
+        0x0, 0x0, 0x1, 0x2, 0x0, 0x0, 0x0, 0x81, 0x3, 0x3, 0x2, 0x7, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0,
+    ];
+    assert_eq!(
+        Join::from(data).unwrap(),
+        Join {
+            burn_transactions: Some(vec![vec![0x82, 0x1, 0x2]]),
+            proofs: Some(vec![vec![0x81, 0x3]]),
+            counter: Some(7),
+        }
+    );
+}
+
+#[test]
+#[cfg(feature = "ft-lock")]
+fn lock() {
+    // lock_status (1): 1, counter (2): 9
+    let data = vec![
+        0x1, 0x2, 0x1, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x2, 0x2, 0x9, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0,
+    ];
+    assert_eq!(
+        Lock::from(data).unwrap(),
+        Lock {
+            lock_status: Some(1),
+            counter: Some(9),
+        }
+    );
+}
+
+#[test]
+#[cfg(feature = "ft-unlock")]
+fn unlock() {
+    // counter (1): 10
+    let data = vec![0x1, 0x2, 0xa, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0];
+    assert_eq!(Unlock::from(data).unwrap(), Unlock { counter: Some(10) });
+}
+
+#[test]
+#[cfg(feature = "ft-token-data")]
+fn token_data() {
+    // type_id (1): [0x2, 0x0, 0x3], value (2): 1000, counter (3): 2, locked (4): 0, timeout (5): 0
+    let data = vec![
+        0x1, 0x1, 0x3, 0x0, 0x0, 0x0, 0x2, 0x0, 0x3, 0x2, 0x2, 0xe8, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x3, 0x2, 0x2, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x4, 0x2, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x5, 0x2, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    ];
+    assert_eq!(
+        TokenData::from(data).unwrap(),
+        TokenData {
+            type_id: Some(vec![0x2, 0x0, 0x3]),
+            value: Some(1000),
+            counter: Some(2),
+            locked: Some(0),
+            timeout: Some(0),
+        }
+    );
+    // type_id (1): [0x2, 0x0, 0x3], value (2): 1, counter (3): 7, locked (4): 1, timeout (5): 500
+    let data = vec![
+        0x1, 0x1, 0x3, 0x0, 0x0, 0x0, 0x2, 0x0, 0x3, 0x2, 0x2, 0x1, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x3, 0x2, 0x7, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x4, 0x2, 0x1, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x5, 0x2, 0xf4, 0x1, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    ];
+    assert_eq!(
+        TokenData::from(data).unwrap(),
+        TokenData {
+            type_id: Some(vec![0x2, 0x0, 0x3]),
+            value: Some(1),
+            counter: Some(7),
+            locked: Some(1),
+            timeout: Some(500),
+        }
+    );
+}
+
+#[test]
+#[cfg(feature = "ft-type-data")]
+fn type_data() {
+    // symbol (2): "EUR", name (3): "euro", decimals (4): 2
+    let data = vec![
+        0x2, 0x4, 0x3, 0x0, 0x0, 0x0, 0x45, 0x55, 0x52, 0x3, 0x4, 0x4, 0x0, 0x0, 0x0, 0x65, 0x75,
+        0x72, 0x6f, 0x4, 0x3, 0x2, 0x0, 0x0, 0x0,
+    ];
+    assert_eq!(
+        TypeData::from(data).unwrap(),
+        TypeData {
+            parent_id: None,
+            symbol: Some("EUR".to_string()),
+            name: Some("euro".to_string()),
+            decimals: Some(2),
+            icon: None,
+        }
+    );
+    // parent_id (1): [0x2, 0x0, 0x1], symbol (2): "C", decimals (4): 0, icon (5): image/svg+xml "<svg>"
+    let data = vec![
+        0x1, 0x1, 0x3, 0x0, 0x0, 0x0, 0x2, 0x0, 0x1, 0x2, 0x4, 0x1, 0x0, 0x0, 0x0, 0x43, 0x4, 0x3,
+        0x0, 0x0, 0x0, 0x0, 0x5, 0x1, 0x1e, 0x0, 0x0, 0x0, 0x1, 0x4, 0xd, 0x0, 0x0, 0x0, 0x69,
+        0x6d, 0x61, 0x67, 0x65, 0x2f, 0x73, 0x76, 0x67, 0x2b, 0x78, 0x6d, 0x6c, 0x2, 0x1, 0x5, 0x0,
+        0x0, 0x0, 0x3c, 0x73, 0x76, 0x67, 0x3e,
+    ];
+    assert_eq!(
+        TypeData::from(data).unwrap(),
+        TypeData {
+            parent_id: Some(vec![0x2, 0x0, 0x1]),
+            symbol: Some("C".to_string()),
+            name: None,
+            decimals: Some(0),
+            icon: Some(Icon {
+                typ: Some("image/svg+xml".to_string()),
+                data: Some(vec![0x3c, 0x73, 0x76, 0x67, 0x3e]),
+            }),
+        }
+    );
+}
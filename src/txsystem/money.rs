@@ -2,14 +2,36 @@
 Money transaction system APIs and data structures.
 */
 
-#[cfg(any(feature = "money-transfer", feature = "money-split"))]
-extern crate alloc;
-#[cfg(any(feature = "money-transfer", feature = "money-split"))]
+#[cfg(any(
+    feature = "money-split",
+    feature = "money-transfer-dc",
+    feature = "money-swap-dc",
+    feature = "money-transfer-fee-credit",
+    feature = "money-reclaim-fee-credit"
+))]
 use alloc::vec::Vec;
+extern crate alloc;
+
+#[cfg(any(
+    feature = "money-transfer",
+    feature = "money-split",
+    feature = "money-transfer-dc",
+    feature = "money-swap-dc",
+    feature = "money-lock",
+    feature = "money-unlock",
+    feature = "money-transfer-fee-credit",
+    feature = "money-reclaim-fee-credit",
+    feature = "money-bill-data"
+))]
+use crate::{AbDecode, evaluation_ctx};
 
-#[cfg(any(feature = "money-transfer", feature = "money-split"))]
-use crate::{error::error_code, evaluation_ctx};
+#[cfg(feature = "money-split")]
+use crate::decoder::Value;
+#[cfg(any(feature = "money-swap-dc", feature = "money-reclaim-fee-credit"))]
+use crate::handle::{Bytes, Handle};
 
+#[cfg(feature = "money-bill-data")]
+use crate::txsystem::ERR_UNIT_NOT_FOUND;
 use crate::{error::Error, txsystem::TxOrder};
 
 pub const SYSTEM_ID: u32 = 1;
@@ -18,6 +40,18 @@ pub const SYSTEM_ID: u32 = 1;
 const PAYLOAD_TYPE_TRANSFER: u32 = 1;
 #[cfg(feature = "money-split")]
 const PAYLOAD_TYPE_SPLIT: u32 = 2;
+#[cfg(feature = "money-transfer-dc")]
+const PAYLOAD_TYPE_TRANSFER_DC: u32 = 3;
+#[cfg(feature = "money-swap-dc")]
+const PAYLOAD_TYPE_SWAP_DC: u32 = 4;
+#[cfg(feature = "money-lock")]
+const PAYLOAD_TYPE_LOCK: u32 = 5;
+#[cfg(feature = "money-unlock")]
+const PAYLOAD_TYPE_UNLOCK: u32 = 6;
+#[cfg(feature = "money-transfer-fee-credit")]
+const PAYLOAD_TYPE_TRANSFER_FEE_CREDIT: u32 = 14;
+#[cfg(feature = "money-reclaim-fee-credit")]
+const PAYLOAD_TYPE_RECLAIM_FEE_CREDIT: u32 = 15;
 
 /// different money tx attributes
 pub enum TxKind {
@@ -25,61 +59,445 @@ pub enum TxKind {
     Transfer(Transfer),
     #[cfg(feature = "money-split")]
     Split(Split),
+    #[cfg(feature = "money-transfer-dc")]
+    TransferDC(TransferDC),
+    #[cfg(feature = "money-swap-dc")]
+    SwapDC(SwapDC),
+    #[cfg(feature = "money-lock")]
+    Lock(Lock),
+    #[cfg(feature = "money-unlock")]
+    Unlock(Unlock),
+    #[cfg(feature = "money-transfer-fee-credit")]
+    TransferFeeCredit(TransferFeeCredit),
+    #[cfg(feature = "money-reclaim-fee-credit")]
+    ReclaimFeeCredit(ReclaimFeeCredit),
 }
 
 pub fn tx_attributes(txo: &TxOrder) -> Result<TxKind, Error> {
-    #[cfg(any(feature = "money-transfer", feature = "money-split"))]
+    #[cfg(any(
+        feature = "money-transfer",
+        feature = "money-split",
+        feature = "money-transfer-dc",
+        feature = "money-swap-dc",
+        feature = "money-lock",
+        feature = "money-unlock",
+        feature = "money-transfer-fee-credit",
+        feature = "money-reclaim-fee-credit"
+    ))]
     let data = evaluation_ctx::tx_attributes(txo.handle, 1);
     match txo.typ {
         #[cfg(feature = "money-transfer")]
         PAYLOAD_TYPE_TRANSFER => Ok(TxKind::Transfer(Transfer::from(data)?)),
         #[cfg(feature = "money-split")]
         PAYLOAD_TYPE_SPLIT => Ok(TxKind::Split(Split::from(data)?)),
+        #[cfg(feature = "money-transfer-dc")]
+        PAYLOAD_TYPE_TRANSFER_DC => Ok(TxKind::TransferDC(TransferDC::from(data)?)),
+        #[cfg(feature = "money-swap-dc")]
+        PAYLOAD_TYPE_SWAP_DC => Ok(TxKind::SwapDC(SwapDC::from(data)?)),
+        #[cfg(feature = "money-lock")]
+        PAYLOAD_TYPE_LOCK => Ok(TxKind::Lock(Lock::from(data)?)),
+        #[cfg(feature = "money-unlock")]
+        PAYLOAD_TYPE_UNLOCK => Ok(TxKind::Unlock(Unlock::from(data)?)),
+        #[cfg(feature = "money-transfer-fee-credit")]
+        PAYLOAD_TYPE_TRANSFER_FEE_CREDIT => {
+            Ok(TxKind::TransferFeeCredit(TransferFeeCredit::from(data)?))
+        }
+        #[cfg(feature = "money-reclaim-fee-credit")]
+        PAYLOAD_TYPE_RECLAIM_FEE_CREDIT => {
+            Ok(TxKind::ReclaimFeeCredit(ReclaimFeeCredit::from(data)?))
+        }
         _ => Err(Error::new(1)),
     }
 }
 
 #[cfg(feature = "money-transfer")]
-#[derive(Default)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+#[derive(Default, AbDecode)]
 pub struct Transfer {
+    #[ab(tag = 1)]
     pub value: u64,
+    #[ab(tag = 2)]
     pub counter: u64,
 }
 
-#[cfg(feature = "money-transfer")]
-impl Transfer {
-    pub fn from(input: Vec<u8>) -> Result<Self, Error> {
-        let mut r = Self::default();
-        for fld in crate::decoder::TagValueIter::new(&input) {
-            match fld {
-                (1, v) => r.value = v.try_into().or_else(error_code(1))?,
-                (2, v) => r.counter = v.try_into().or_else(error_code(2))?,
-                _ => (), // unknown field to us, ignore
-            }
-        }
-        Ok(r)
-    }
-}
-
 #[cfg(feature = "money-split")]
-#[derive(Default)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+#[derive(Default, AbDecode)]
 pub struct Split {
-    //TargetUnits    []*TargetUnit - todo: add field
+    #[ab(tag = 1)]
     pub remaining_value: u64,
+    #[ab(tag = 2)]
     pub counter: u64,
+    /// new units created by the split
+    #[ab(tag = 3)]
+    pub target_units: Vec<TargetUnit>,
 }
 
+/**
+New unit created by the "split bill" transaction.
+*/
 #[cfg(feature = "money-split")]
-impl Split {
-    pub fn from(input: Vec<u8>) -> Result<Self, Error> {
-        let mut r = Self::default();
-        for fld in crate::decoder::TagValueIter::new(&input) {
-            match fld {
-                (1, v) => r.remaining_value = v.try_into().or_else(error_code(1))?,
-                (2, v) => r.counter = v.try_into().or_else(error_code(2))?,
-                _ => (), // unknown field to us, ignore
-            }
+#[cfg_attr(test, derive(PartialEq, Debug))]
+#[derive(Default, AbDecode)]
+pub struct TargetUnit {
+    #[ab(tag = 1)]
+    pub amount: u64,
+    #[ab(tag = 2)]
+    pub owner_predicate: Vec<u8>,
+}
+
+/// target units are encoded as array of tag-value encoded byte slices.
+#[cfg(feature = "money-split")]
+impl TryFrom<Value> for Vec<TargetUnit> {
+    type Error = Error;
+
+    fn try_from(v: Value) -> Result<Self, Self::Error> {
+        match v {
+            Value::Array(items) => items
+                .into_iter()
+                .map(|v| TargetUnit::from(Vec::<u8>::try_from(v)?))
+                .collect(),
+            _ => Err(Error::new(1)),
+        }
+    }
+}
+
+/**
+Attributes of the "transfer bill to dust collector" transaction.
+*/
+#[cfg(feature = "money-transfer-dc")]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+#[derive(Default, AbDecode)]
+pub struct TransferDC {
+    #[ab(tag = 1)]
+    pub value: u64,
+    /// ID of the bill the value is swapped into
+    #[ab(tag = 2)]
+    pub target_unit_id: Vec<u8>,
+    #[ab(tag = 3)]
+    pub target_unit_counter: u64,
+    #[ab(tag = 4)]
+    pub counter: u64,
+}
+
+/**
+Attributes of the "swap bills with dust collector" transaction.
+*/
+#[cfg(feature = "money-swap-dc")]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+#[derive(Default, AbDecode)]
+pub struct SwapDC {
+    /// CBOR encoded proofs of the "transfer to dust collector" transactions
+    #[ab(tag = 1)]
+    pub dust_transfer_proofs: Vec<Vec<u8>>,
+}
+
+#[cfg(feature = "money-swap-dc")]
+impl SwapDC {
+    /// returns handles of host variables of the dust transfer proofs.
+    pub fn proof_handles(&self) -> Vec<Handle<Bytes>> {
+        self.dust_transfer_proofs
+            .iter()
+            .map(|p| evaluation_ctx::add_var(p.clone()))
+            .collect()
+    }
+}
+
+/**
+Attributes of the "lock bill" transaction.
+*/
+#[cfg(feature = "money-lock")]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+#[derive(Default, AbDecode)]
+pub struct Lock {
+    /// non-zero value, the reason of the locking
+    #[ab(tag = 1)]
+    pub lock_status: u64,
+    #[ab(tag = 2)]
+    pub counter: u64,
+}
+
+/**
+Attributes of the "unlock bill" transaction.
+*/
+#[cfg(feature = "money-unlock")]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+#[derive(Default, AbDecode)]
+pub struct Unlock {
+    #[ab(tag = 1)]
+    pub counter: u64,
+}
+
+/**
+Attributes of the "transfer fee credit" transaction.
+*/
+#[cfg(feature = "money-transfer-fee-credit")]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+#[derive(Default, AbDecode)]
+pub struct TransferFeeCredit {
+    /// amount to transfer
+    #[ab(tag = 1)]
+    pub amount: u64,
+    /// partition the fee credit is added in
+    #[ab(tag = 2)]
+    pub target_partition: u32,
+    /// ID of the fee credit record in the target partition
+    #[ab(tag = 3)]
+    pub target_record_id: Vec<u8>,
+    #[ab(tag = 4)]
+    pub latest_addition_time: u64,
+    /// counter of the target fee credit record, `None` when the record
+    /// doesn't exist yet
+    #[ab(tag = 5)]
+    pub target_unit_counter: Option<u64>,
+    #[ab(tag = 6)]
+    pub counter: u64,
+}
+
+/**
+Attributes of the "reclaim fee credit" transaction.
+*/
+#[cfg(feature = "money-reclaim-fee-credit")]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+#[derive(Default, AbDecode)]
+pub struct ReclaimFeeCredit {
+    /// CBOR encoded proof of the "close fee credit" transaction
+    #[ab(tag = 1)]
+    pub close_fee_credit_proof: Vec<u8>,
+    #[ab(tag = 2)]
+    pub counter: u64,
+}
+
+#[cfg(feature = "money-reclaim-fee-credit")]
+impl ReclaimFeeCredit {
+    /// returns handle of the host variable of the close fee credit proof.
+    pub fn proof_handle(&self) -> Handle<Bytes> {
+        evaluation_ctx::add_var(self.close_fee_credit_proof.clone())
+    }
+}
+
+/**
+Data of a bill (money partition unit).
+
+Unit data can be loaded using [`BillData::load`] method.
+*/
+#[cfg(feature = "money-bill-data")]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+#[derive(Default, AbDecode)]
+pub struct BillData {
+    #[ab(tag = 1)]
+    pub value: Option<u64>,
+    #[ab(tag = 2)]
+    pub counter: Option<u64>,
+    /// locked status of the bill, non-zero value means locked
+    #[ab(tag = 3)]
+    pub locked: Option<u64>,
+    /// round number of the last update of the bill
+    #[ab(tag = 4)]
+    pub last_update: Option<u64>,
+}
+
+#[cfg(feature = "money-bill-data")]
+impl BillData {
+    pub const TAG_VER: u8 = 1;
+
+    /// loads the data of the bill, [`ERR_UNIT_NOT_FOUND`] is returned when the unit has no data.
+    pub fn load(unit_id: &[u8], committed: bool) -> Result<Self, Error> {
+        let ud = evaluation_ctx::unit_data(unit_id, committed, Self::TAG_VER);
+        if ud.is_empty() {
+            return Err(Error::new(ERR_UNIT_NOT_FOUND));
         }
-        Ok(r)
+        Self::from(ud)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused_imports)]
+    use alloc::vec;
+
+    #[test]
+    #[cfg(feature = "money-split")]
+    fn split() {
+        let data = [
+            0x1, 0x2, 0x64, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, // remaining value 100
+            0x2, 0x2, 0x7, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, // counter 7
+            0x3, 0x5, 0x2, 0x0, 0x0, 0x0, // two target units
+            0x1, 0x10, 0x0, 0x0, 0x0, // first unit, 16 bytes
+            0x1, 0x2, 0xa, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x2, 0x1, 0x0, 0x0, 0x0, 0x0, 0x1,
+            0x8, 0x0, 0x0, 0x0, // second unit, 8 bytes
+            0x2, 0x1, 0x2, 0x0, 0x0, 0x0, 0xab, 0xcd,
+        ];
+        assert_eq!(
+            Split::from(data).unwrap(),
+            Split {
+                remaining_value: 100,
+                counter: 7,
+                target_units: vec![
+                    TargetUnit {
+                        amount: 10,
+                        owner_predicate: vec![],
+                    },
+                    TargetUnit {
+                        amount: 0,
+                        owner_predicate: vec![0xab, 0xcd],
+                    },
+                ],
+            }
+        );
+
+        // target unit is not byte slice
+        let data = [0x3, 0x5, 0x1, 0x0, 0x0, 0x0, 0x3, 0x1, 0x0, 0x0, 0x0];
+        assert_eq!(Split::from(data).unwrap_err().code(), 0x0103);
+    }
+
+    #[test]
+    #[cfg(feature = "money-transfer-dc")]
+    fn transfer_dc() {
+        let data = [
+            0x1, 0x2, 0x5, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, // value 5
+            0x2, 0x1, 0x2, 0x0, 0x0, 0x0, 0x1, 0x2, // target unit
+            0x3, 0x2, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, // target counter 3
+            0x4, 0x2, 0x1, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, // counter 1
+        ];
+        assert_eq!(
+            TransferDC::from(data).unwrap(),
+            TransferDC {
+                value: 5,
+                target_unit_id: vec![1, 2],
+                target_unit_counter: 3,
+                counter: 1,
+            }
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "money-swap-dc")]
+    fn swap_dc() {
+        let data = [
+            0x1, 0x5, 0x2, 0x0, 0x0, 0x0, // two proofs
+            0x1, 0x1, 0x0, 0x0, 0x0, 0x80, // []
+            0x1, 0x2, 0x0, 0x0, 0x0, 0x81, 0x1, // [1]
+        ];
+        assert_eq!(
+            SwapDC::from(data).unwrap(),
+            SwapDC {
+                dust_transfer_proofs: vec![vec![0x80], vec![0x81, 0x1]],
+            }
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "money-lock")]
+    fn lock() {
+        let data = [
+            0x1, 0x2, 0x2, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, // lock status 2
+            0x2, 0x2, 0x8, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, // counter 8
+        ];
+        assert_eq!(
+            Lock::from(data).unwrap(),
+            Lock {
+                lock_status: 2,
+                counter: 8,
+            }
+        );
+
+        // lock status is not integer
+        let data = [0x1, 0x1, 0x1, 0x0, 0x0, 0x0, 0x2];
+        assert_eq!(Lock::from(data).unwrap_err().code(), 0x0101);
+    }
+
+    #[test]
+    #[cfg(feature = "money-unlock")]
+    fn unlock() {
+        let data = [0x1, 0x2, 0x9, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0]; // counter 9
+        assert_eq!(Unlock::from(data).unwrap(), Unlock { counter: 9 });
+        assert_eq!(Unlock::from([]).unwrap(), Unlock { counter: 0 });
+    }
+
+    #[test]
+    #[cfg(feature = "money-reclaim-fee-credit")]
+    fn reclaim_fee_credit() {
+        let data = [
+            0x1, 0x1, 0x3, 0x0, 0x0, 0x0, 0x82, 0x1, 0x2, // proof [1, 2]
+            0x2, 0x2, 0x5, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, // counter 5
+        ];
+        assert_eq!(
+            ReclaimFeeCredit::from(data).unwrap(),
+            ReclaimFeeCredit {
+                close_fee_credit_proof: vec![0x82, 0x1, 0x2],
+                counter: 5,
+            }
+        );
+
+        // truncated proof
+        let data = [0x1, 0x1, 0x3, 0x0, 0x0, 0x0, 0x82];
+        assert_eq!(
+            ReclaimFeeCredit::from(data).unwrap_err().code(),
+            crate::decoder::ERR_TRUNCATED as u64
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "money-transfer-fee-credit")]
+    fn transfer_fee_credit() {
+        let data = [
+            0x1, 0x2, 0xe8, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, // amount 1000
+            0x2, 0x3, 0x2, 0x0, 0x0, 0x0, // partition 2
+            0x3, 0x1, 0x1, 0x0, 0x0, 0x0, 0xf, // record ID
+            0x4, 0x2, 0x9, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, // latest addition time 9
+            0x6, 0x2, 0x4, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, // counter 4
+        ];
+        assert_eq!(
+            TransferFeeCredit::from(data).unwrap(),
+            TransferFeeCredit {
+                amount: 1000,
+                target_partition: 2,
+                target_record_id: vec![0xf],
+                latest_addition_time: 9,
+                target_unit_counter: None,
+                counter: 4,
+            }
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "money-bill-data")]
+    fn bill_data() {
+        let data = [
+            0x1, 0x2, 0x40, 0x42, 0xf, 0x0, 0x0, 0x0, 0x0, 0x0, // value 1000000
+            0x2, 0x2, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, // counter 3
+            0x3, 0x2, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, // not locked
+            0x4, 0x2, 0x10, 0x27, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, // last update 10000
+        ];
+        assert_eq!(
+            BillData::from(data).unwrap(),
+            BillData {
+                value: Some(1000000),
+                counter: Some(3),
+                locked: Some(0),
+                last_update: Some(10000),
+            }
+        );
+        assert_eq!(BillData::from([]).unwrap(), BillData::default());
+    }
+
+    #[test]
+    #[cfg(all(feature = "mock-host", feature = "money-bill-data"))]
+    fn bill_data_load() {
+        use crate::host::mock::{Encoder, MockHost};
+
+        MockHost::new()
+            .unit_data(&[1], Encoder::new().u64(1, 50).u64(2, 1).finish())
+            .install();
+
+        let bd = BillData::load(&[1], true).unwrap();
+        assert_eq!(bd.value, Some(50));
+        assert_eq!(bd.counter, Some(1));
+        assert_eq!(
+            BillData::load(&[2], true).unwrap_err().code(),
+            ERR_UNIT_NOT_FOUND as u64
+        );
     }
 }
@@ -1,32 +1,60 @@
+#[cfg(any(
+    feature = "nft-create-type",
+    feature = "nft-mint-token",
+    feature = "nft-token-data",
+    feature = "nft-type-data"
+))]
+use alloc::string::String;
 #[cfg(any(
     feature = "nft-create-type",
     feature = "nft-mint-token",
     feature = "nft-transfer",
-    feature = "nft-update"
+    feature = "nft-update",
+    feature = "nft-token-data",
+    feature = "nft-type-data"
 ))]
-use alloc::{string::String, vec::Vec};
+use alloc::vec::Vec;
 extern crate alloc;
 
 #[cfg(any(
     feature = "nft-create-type",
     feature = "nft-mint-token",
     feature = "nft-transfer",
-    feature = "nft-update"
+    feature = "nft-update",
+    feature = "nft-lock",
+    feature = "nft-unlock",
+    feature = "nft-token-data",
+    feature = "nft-type-data"
 ))]
-use crate::{
-    decoder::{self},
-    error::error_code,
-    evaluation_ctx,
-};
+use crate::{AbDecode, evaluation_ctx};
 
+#[cfg(feature = "nft-type-data")]
+use crate::txsystem::token::Icon;
 use crate::{error::Error, txsystem::TxOrder};
 
+#[cfg(any(feature = "nft-token-data", feature = "nft-type-data"))]
+pub use crate::txsystem::ERR_UNIT_NOT_FOUND;
+/// the type hierarchy contains a cycle
+#[cfg(feature = "nft-type-data")]
+pub const ERR_TYPE_CYCLE: u8 = 10;
+/// the type hierarchy is deeper than [`MAX_TYPE_CHAIN_DEPTH`]
+#[cfg(feature = "nft-type-data")]
+pub const ERR_TYPE_CHAIN_TOO_DEEP: u8 = 11;
+
+/// max number of types [`type_chain`] iterator returns before giving up.
+#[cfg(feature = "nft-type-data")]
+pub const MAX_TYPE_CHAIN_DEPTH: usize = 16;
+
 #[cfg(feature = "nft-create-type")]
 const PAYLOAD_TYPE_NFT_CREATE_TYPE: u32 = 2;
 #[cfg(feature = "nft-mint-token")]
 const PAYLOAD_TYPE_NFT_MINT: u32 = 4;
 #[cfg(feature = "nft-transfer")]
 const PAYLOAD_TYPE_NFT_TRANSFER: u32 = 6;
+#[cfg(feature = "nft-lock")]
+const PAYLOAD_TYPE_NFT_LOCK: u32 = 7;
+#[cfg(feature = "nft-unlock")]
+const PAYLOAD_TYPE_NFT_UNLOCK: u32 = 8;
 #[cfg(feature = "nft-update")]
 const PAYLOAD_TYPE_NFT_UPDATE: u32 = 12;
 
@@ -40,6 +68,10 @@ pub enum TxKind {
     Transfer(Transfer),
     #[cfg(feature = "nft-update")]
     Update(Update),
+    #[cfg(feature = "nft-lock")]
+    Lock(Lock),
+    #[cfg(feature = "nft-unlock")]
+    Unlock(Unlock),
 }
 
 pub fn tx_attributes(txo: &TxOrder) -> Result<TxKind, Error> {
@@ -47,7 +79,9 @@ pub fn tx_attributes(txo: &TxOrder) -> Result<TxKind, Error> {
         feature = "nft-create-type",
         feature = "nft-mint-token",
         feature = "nft-transfer",
-        feature = "nft-update"
+        feature = "nft-update",
+        feature = "nft-lock",
+        feature = "nft-unlock"
     ))]
     // right now we have just one version of all the attribute structs so we can
     // load the data here - as soon as some struct needs to support multiple
@@ -62,46 +96,42 @@ pub fn tx_attributes(txo: &TxOrder) -> Result<TxKind, Error> {
         PAYLOAD_TYPE_NFT_TRANSFER => Ok(TxKind::Transfer(Transfer::from(data)?)),
         #[cfg(feature = "nft-update")]
         PAYLOAD_TYPE_NFT_UPDATE => Ok(TxKind::Update(Update::from(data)?)),
+        #[cfg(feature = "nft-lock")]
+        PAYLOAD_TYPE_NFT_LOCK => Ok(TxKind::Lock(Lock::from(data)?)),
+        #[cfg(feature = "nft-unlock")]
+        PAYLOAD_TYPE_NFT_UNLOCK => Ok(TxKind::Unlock(Unlock::from(data)?)),
         _ => Err(Error::new(0xFF)),
     }
 }
 
 #[cfg(feature = "nft-create-type")]
 #[cfg_attr(test, derive(PartialEq, Debug))]
-#[derive(Default)]
+#[derive(Default, AbDecode)]
 pub struct CreateType {
+    #[ab(tag = 1)]
     pub symbol: Option<String>,
+    #[ab(tag = 2)]
     pub name: Option<String>,
     /// parent type of the type, empty for root type
     /// NB! use Some(empty vec) to distinguish between missing
     /// value and empty value!
+    #[ab(tag = 3)]
     pub type_id: Option<Vec<u8>>,
 }
 
-#[cfg(feature = "nft-create-type")]
-impl CreateType {
-    pub fn from(input: Vec<u8>) -> Result<Self, Error> {
-        let mut ct = Self::default();
-        for fld in decoder::TagValueIter::new(&input) {
-            match fld {
-                (1, v) => ct.symbol = v.try_into().or_else(error_code(1))?,
-                (2, v) => ct.name = v.try_into().or_else(error_code(2))?,
-                (3, v) => ct.type_id = v.try_into().or_else(error_code(3))?,
-                _ => (), // unknown field to us, ignore
-            }
-        }
-        Ok(ct)
-    }
-}
-
 #[cfg(feature = "nft-mint-token")]
 #[cfg_attr(test, derive(PartialEq, Debug))]
-#[derive(Default)]
+#[derive(Default, AbDecode)]
 pub struct Mint {
+    #[ab(tag = 5)]
     pub type_id: Option<Vec<u8>>,
+    #[ab(tag = 1)]
     pub name: Option<String>,
+    #[ab(tag = 2)]
     pub uri: Option<String>,
+    #[ab(tag = 3)]
     pub data: Option<Vec<u8>>,
+    #[ab(tag = 4)]
     pub nonce: Option<u64>,
 }
 
@@ -113,69 +143,91 @@ impl Mint {
         let data = evaluation_ctx::tx_attributes(txo.handle, 1);
         Self::from(data)
     }
-
-    pub fn from(input: Vec<u8>) -> Result<Self, Error> {
-        let mut r = Self::default();
-        for fld in decoder::TagValueIter::new(&input) {
-            match fld {
-                (1, v) => r.name = v.try_into().or_else(error_code(1))?,
-                (2, v) => r.uri = v.try_into().or_else(error_code(2))?,
-                (3, v) => r.data = v.try_into().or_else(error_code(3))?,
-                (4, v) => r.nonce = v.try_into().or_else(error_code(4))?,
-                (5, v) => r.type_id = v.try_into().or_else(error_code(5))?,
-                _ => (), // unknown field to us, ignore
-            }
-        }
-        Ok(r)
-    }
 }
 
 #[cfg(feature = "nft-transfer")]
 #[cfg_attr(test, derive(PartialEq, Debug))]
-#[derive(Default)]
+#[derive(Default, AbDecode)]
 pub struct Transfer {
+    #[ab(tag = 1)]
     pub type_id: Option<Vec<u8>>,
+    #[ab(tag = 2)]
     pub counter: Option<u64>,
 }
 
-#[cfg(feature = "nft-transfer")]
-impl Transfer {
-    pub fn from(input: Vec<u8>) -> Result<Self, Error> {
-        let mut r = Self::default();
-        for fld in decoder::TagValueIter::new(&input) {
-            match fld {
-                (1, v) => r.type_id = v.try_into().or_else(error_code(fld.0))?,
-                (2, v) => r.counter = v.try_into().or_else(error_code(fld.0))?,
-                _ => (), // unknown field to us, ignore
-            }
-        }
-        Ok(r)
-    }
-}
-
 /**
 Data of the "update NFT token" transaction.
 */
 #[cfg(feature = "nft-update")]
 #[cfg_attr(test, derive(PartialEq, Debug))]
-#[derive(Default)]
+#[derive(Default, AbDecode)]
 pub struct Update {
+    #[ab(tag = 1)]
     pub data: Option<Vec<u8>>,
+    #[ab(tag = 2)]
     pub counter: Option<u64>,
 }
 
-#[cfg(feature = "nft-update")]
-impl Update {
-    pub fn from(input: Vec<u8>) -> Result<Self, Error> {
-        let mut r = Self::default();
-        for fld in decoder::TagValueIter::new(&input) {
-            match fld {
-                (1, v) => r.data = v.try_into().or_else(error_code(1))?,
-                (2, v) => r.counter = v.try_into().or_else(error_code(2))?,
-                _ => (), // unknown field to us, ignore
-            }
+/**
+Data of the "lock token" transaction.
+*/
+#[cfg(feature = "nft-lock")]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+#[derive(Default, AbDecode)]
+pub struct Lock {
+    /// non-zero value, the reason of the locking
+    #[ab(tag = 1)]
+    pub lock_status: Option<u64>,
+    #[ab(tag = 2)]
+    pub counter: Option<u64>,
+}
+
+#[cfg(feature = "nft-lock")]
+impl Lock {
+    /// Lock status the token will have after the transaction.
+    pub fn status(&self) -> LockStatus {
+        LockStatus::from(self.lock_status.unwrap_or_default())
+    }
+}
+
+/**
+Data of the "unlock token" transaction.
+*/
+#[cfg(feature = "nft-unlock")]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+#[derive(Default, AbDecode)]
+pub struct Unlock {
+    #[ab(tag = 1)]
+    pub counter: Option<u64>,
+}
+
+/**
+Lock status of a token.
+
+Zero means the token is not locked, any other value is the reason of the
+locking (set by the "lock token" transaction).
+*/
+#[cfg(any(feature = "nft-lock", feature = "nft-token-data"))]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LockStatus {
+    Unlocked,
+    Locked(u64),
+}
+
+#[cfg(any(feature = "nft-lock", feature = "nft-token-data"))]
+impl LockStatus {
+    pub fn is_locked(&self) -> bool {
+        matches!(self, LockStatus::Locked(_))
+    }
+}
+
+#[cfg(any(feature = "nft-lock", feature = "nft-token-data"))]
+impl From<u64> for LockStatus {
+    fn from(v: u64) -> Self {
+        match v {
+            0 => LockStatus::Unlocked,
+            n => LockStatus::Locked(n),
         }
-        Ok(r)
     }
 }
 
@@ -189,40 +241,52 @@ data structure with [`TokenData::from`] method.
 */
 #[cfg(feature = "nft-token-data")]
 #[cfg_attr(test, derive(PartialEq, Debug))]
-#[derive(Default)]
+#[derive(Default, AbDecode)]
 pub struct TokenData {
+    #[ab(tag = 1)]
     pub type_id: Option<Vec<u8>>,
+    #[ab(tag = 2)]
     pub name: Option<String>,
+    #[ab(tag = 3)]
     pub uri: Option<String>,
+    #[ab(tag = 4)]
     pub data: Option<Vec<u8>>,
+    #[ab(tag = 5)]
     pub counter: Option<u64>,
     /// locked status of the token, non-zero value means locked
+    #[ab(tag = 6)]
     pub locked: Option<u64>,
+    // TODO: tags 7 and 8 are not verified against the host's encoding, the
+    // generated nft_test.rs only has data without these fields - regenerate it
+    // with predicates set (Test_generate_NFT_DecodeTests).
+    /// current owner (bearer) predicate of the token
+    #[ab(tag = 7)]
+    pub owner_predicate: Option<Vec<u8>>,
+    /// predicate which must be satisfied to update the data of the token
+    #[ab(tag = 8)]
+    pub data_update_predicate: Option<Vec<u8>>,
 }
 
 #[cfg(feature = "nft-token-data")]
 impl TokenData {
     pub const TAG_VER: u8 = 1;
 
-    pub fn load(unit_id: &Vec<u8>, committed: bool) -> Result<Self, Error> {
+    /// loads the data of the token, [`ERR_UNIT_NOT_FOUND`] is returned when the unit has no data.
+    pub fn load(unit_id: &[u8], committed: bool) -> Result<Self, Error> {
         let ud = evaluation_ctx::unit_data(unit_id, committed, Self::TAG_VER);
+        if ud.is_empty() {
+            return Err(Error::new(ERR_UNIT_NOT_FOUND));
+        }
         Self::from(ud)
     }
 
-    pub fn from(input: Vec<u8>) -> Result<Self, Error> {
-        let mut r = Self::default();
-        for fld in decoder::TagValueIter::new(&input) {
-            match fld {
-                (1, v) => r.type_id = v.try_into().or_else(error_code(fld.0))?,
-                (2, v) => r.name = v.try_into().or_else(error_code(fld.0))?,
-                (3, v) => r.uri = v.try_into().or_else(error_code(fld.0))?,
-                (4, v) => r.data = v.try_into().or_else(error_code(fld.0))?,
-                (5, v) => r.counter = v.try_into().or_else(error_code(fld.0))?,
-                (6, v) => r.locked = v.try_into().or_else(error_code(fld.0))?,
-                _ => (), // unknown field to us, ignore
-            }
-        }
-        Ok(r)
+    /// Lock status of the token, missing value is interpreted as "unlocked".
+    pub fn lock_status(&self) -> LockStatus {
+        LockStatus::from(self.locked.unwrap_or_default())
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.lock_status().is_locked()
     }
 }
 
@@ -231,37 +295,361 @@ Data of an NFT token type.
 */
 #[cfg(feature = "nft-type-data")]
 #[cfg_attr(test, derive(PartialEq, Debug))]
-#[derive(Default)]
+#[derive(Default, AbDecode)]
 pub struct TypeData {
     /// parent type of the type, empty means "root type"
+    #[ab(tag = 1)]
     pub parent_id: Option<Vec<u8>>,
+    #[ab(tag = 2)]
     pub symbol: Option<String>,
+    #[ab(tag = 3)]
     pub name: Option<String>,
+    // TODO: tags 4..=8 are not verified against the host's encoding, the
+    // generated nft_test.rs only has data without these fields - regenerate it
+    // with icon and predicates set (Test_generate_NFT_DecodeTests).
+    #[ab(tag = 4)]
+    pub icon: Option<Icon>,
+    /// predicate which must be satisfied to create subtypes of the type
+    #[ab(tag = 5)]
+    pub sub_type_creation_predicate: Option<Vec<u8>>,
+    /// predicate which must be satisfied to mint tokens of the type
+    #[ab(tag = 6)]
+    pub token_minting_predicate: Option<Vec<u8>>,
+    /// predicate inherited by all the tokens of the type, must be satisfied
+    /// in addition to the owner predicate of the token
+    #[ab(tag = 7)]
+    pub token_type_owner_predicate: Option<Vec<u8>>,
+    /// predicate inherited by all the tokens of the type, must be satisfied
+    /// in addition to the data update predicate of the token
+    #[ab(tag = 8)]
+    pub data_update_predicate: Option<Vec<u8>>,
 }
 
 #[cfg(feature = "nft-type-data")]
 impl TypeData {
     pub const TAG_VER: u8 = 1;
 
-    pub fn load(unit_id: &Vec<u8>, committed: bool) -> Result<Self, Error> {
+    /// loads the data of the token type, [`ERR_UNIT_NOT_FOUND`] is returned when the unit has no data.
+    pub fn load(unit_id: &[u8], committed: bool) -> Result<Self, Error> {
         let ud = evaluation_ctx::unit_data(unit_id, committed, Self::TAG_VER);
+        if ud.is_empty() {
+            return Err(Error::new(ERR_UNIT_NOT_FOUND));
+        }
         Self::from(ud)
     }
+}
 
-    pub fn from(input: Vec<u8>) -> Result<Self, Error> {
-        let mut r = Self::default();
-        for fld in decoder::TagValueIter::new(&input) {
-            match fld {
-                (1, v) => r.parent_id = v.try_into().or_else(error_code(fld.0))?,
-                (2, v) => r.symbol = v.try_into().or_else(error_code(fld.0))?,
-                (3, v) => r.name = v.try_into().or_else(error_code(fld.0))?,
-                _ => (), // unknown field to us, ignore
-            }
+/**
+Returns iterator over the given NFT type and all its ancestor types.
+
+The first item is the type `type_id` itself, followed by its parent type
+and so on until the root type (type with empty parent ID) is reached. Each
+item is the ID of the type and the type data.
+
+Iteration ends with an error (and no more items are returned after that) when
+ - the data of the type is not found: [`ERR_UNIT_NOT_FOUND`];
+ - the type is already visited, ie the hierarchy contains cycle: [`ERR_TYPE_CYCLE`];
+ - more than [`MAX_TYPE_CHAIN_DEPTH`] types have been returned: [`ERR_TYPE_CHAIN_TOO_DEEP`];
+ - decoding the type data fails.
+*/
+#[cfg(feature = "nft-type-data")]
+pub fn type_chain(
+    type_id: &[u8],
+    committed: bool,
+) -> impl Iterator<Item = Result<(Vec<u8>, TypeData), Error>> {
+    TypeChain {
+        next: Some(type_id.to_vec()),
+        visited: Vec::new(),
+        committed,
+    }
+}
+
+#[cfg(feature = "nft-type-data")]
+struct TypeChain {
+    next: Option<Vec<u8>>,
+    visited: Vec<Vec<u8>>,
+    committed: bool,
+}
+
+#[cfg(feature = "nft-type-data")]
+impl Iterator for TypeChain {
+    type Item = Result<(Vec<u8>, TypeData), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let id = self.next.take()?;
+        if self.visited.contains(&id) {
+            return Some(Err(Error::new(ERR_TYPE_CYCLE)));
+        }
+        if self.visited.len() >= MAX_TYPE_CHAIN_DEPTH {
+            return Some(Err(Error::new(ERR_TYPE_CHAIN_TOO_DEEP)));
         }
-        Ok(r)
+
+        let td = match TypeData::load(&id, self.committed) {
+            Ok(td) => td,
+            Err(err) => return Some(Err(err)),
+        };
+        self.next = td.parent_id.clone().filter(|p| !p.is_empty());
+        self.visited.push(id.clone());
+        Some(Ok((id, td)))
     }
 }
 
+/**
+Returns `true` when the type of the NFT token `unit_id` is `ancestor_type_id`
+or any subtype of it.
+
+Committed state of the units is used, type of the token and type hierarchy
+do not change during the lifetime of the token.
+*/
+#[cfg(all(feature = "nft-token-data", feature = "nft-type-data"))]
+pub fn token_is_of_type(unit_id: &[u8], ancestor_type_id: &[u8]) -> Result<bool, Error> {
+    // token without type fails with ERR_UNIT_NOT_FOUND as there is no type with empty ID
+    let type_id = TokenData::load(unit_id, true)?.type_id.unwrap_or_default();
+    for item in type_chain(&type_id, true) {
+        let (id, _) = item?;
+        if id == ancestor_type_id {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
 // test module generated by Go backend
 #[cfg(test)]
 mod nft_test;
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+    #[allow(unused_imports)]
+    use alloc::{string::ToString, vec};
+
+    #[test]
+    #[cfg(feature = "nft-lock")]
+    fn lock() {
+        // LockStatus = 3, Counter = 5
+        let data = [
+            0x1, 0x2, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x2, 0x2, 0x5, 0x0, 0x0, 0x0, 0x0,
+            0x0, 0x0, 0x0,
+        ];
+        let lock = Lock::from(data).unwrap();
+        assert_eq!(
+            lock,
+            Lock {
+                lock_status: Some(3),
+                counter: Some(5),
+            }
+        );
+        assert_eq!(lock.status(), LockStatus::Locked(3));
+        assert!(lock.status().is_locked());
+    }
+
+    #[test]
+    #[cfg(feature = "nft-unlock")]
+    fn unlock() {
+        let data = [0x1, 0x2, 0x6, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0];
+        assert_eq!(Unlock::from(data).unwrap(), Unlock { counter: Some(6) });
+    }
+
+    #[test]
+    #[cfg(all(feature = "nft-mint-token", feature = "nft-transfer"))]
+    fn invalid_counter() {
+        // the error of the u64 is chained with the tag (hand written decoders
+        // used to return 0x010104 and 0x010102); tag 4 / tag 2 = String("x")
+        let data = [0x4, 0x4, 0x1, 0x0, 0x0, 0x0, 0x78];
+        assert_eq!(Mint::from(data).unwrap_err().code(), 0x0104);
+        let data = [0x2, 0x4, 0x1, 0x0, 0x0, 0x0, 0x78];
+        assert_eq!(Transfer::from(data).unwrap_err().code(), 0x0102);
+    }
+
+    #[test]
+    #[cfg(feature = "nft-token-data")]
+    fn token_lock_status() {
+        let mut td = TokenData::default();
+        assert_eq!(td.lock_status(), LockStatus::Unlocked);
+        assert!(!td.is_locked());
+
+        td.locked = Some(0);
+        assert_eq!(td.lock_status(), LockStatus::Unlocked);
+        assert!(!td.is_locked());
+
+        td.locked = Some(1);
+        assert_eq!(td.lock_status(), LockStatus::Locked(1));
+        assert!(td.is_locked());
+    }
+
+    #[test]
+    #[cfg(feature = "nft-type-data")]
+    fn type_data_predicates() {
+        let data = [
+            0x1, 0x1, 0x2, 0x0, 0x0, 0x0, 0x1, 0x2, 0x2, 0x4, 0x1, 0x0, 0x0, 0x0, 0x54, 0x4, 0x1,
+            0x18, 0x0, 0x0, 0x0, 0x1, 0x4, 0x9, 0x0, 0x0, 0x0, 0x69, 0x6d, 0x61, 0x67, 0x65, 0x2f,
+            0x70, 0x6e, 0x67, 0x2, 0x1, 0x3, 0x0, 0x0, 0x0, 0x89, 0x50, 0x4e, 0x5, 0x1, 0x4, 0x0,
+            0x0, 0x0, 0x83, 0x0, 0x41, 0x1, 0x6, 0x1, 0x4, 0x0, 0x0, 0x0, 0x83, 0x0, 0x41, 0x2,
+            0x7, 0x1, 0x4, 0x0, 0x0, 0x0, 0x83, 0x0, 0x41, 0x0, 0x8, 0x1, 0x4, 0x0, 0x0, 0x0, 0x83,
+            0x0, 0x41, 0x3,
+        ];
+        assert_eq!(
+            TypeData::from(data).unwrap(),
+            TypeData {
+                parent_id: Some(vec![0x1, 0x2]),
+                symbol: Some("T".to_string()),
+                name: None,
+                icon: Some(Icon {
+                    typ: Some("image/png".to_string()),
+                    data: Some(vec![0x89, 0x50, 0x4e]),
+                }),
+                sub_type_creation_predicate: Some(vec![0x83, 0x0, 0x41, 0x1]),
+                token_minting_predicate: Some(vec![0x83, 0x0, 0x41, 0x2]),
+                token_type_owner_predicate: Some(vec![0x83, 0x0, 0x41, 0x0]),
+                data_update_predicate: Some(vec![0x83, 0x0, 0x41, 0x3]),
+            }
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "nft-token-data")]
+    fn token_data_predicates() {
+        let data = [
+            0x1, 0x1, 0x2, 0x0, 0x0, 0x0, 0x1, 0x2, 0x5, 0x2, 0x1, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+            0x0, 0x7, 0x1, 0x4, 0x0, 0x0, 0x0, 0x83, 0x0, 0x41, 0x1, 0x8, 0x1, 0x4, 0x0, 0x0, 0x0,
+            0x83, 0x0, 0x41, 0x0,
+        ];
+        assert_eq!(
+            TokenData::from(data).unwrap(),
+            TokenData {
+                type_id: Some(vec![0x1, 0x2]),
+                counter: Some(1),
+                owner_predicate: Some(vec![0x83, 0x0, 0x41, 0x1]),
+                data_update_predicate: Some(vec![0x83, 0x0, 0x41, 0x0]),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[cfg(all(feature = "mock-host", feature = "nft-type-data"))]
+    fn type_data(parent_id: &[u8]) -> Vec<u8> {
+        crate::host::mock::Encoder::new()
+            .bytes(1, parent_id)
+            .string(2, "T")
+            .finish()
+    }
+
+    #[test]
+    #[cfg(all(feature = "mock-host", feature = "nft-type-data"))]
+    fn type_chain_ok() {
+        use crate::host::mock::MockHost;
+
+        MockHost::new()
+            .unit_data(&[1], type_data(&[]))
+            .unit_data(&[2], type_data(&[1]))
+            .unit_data(&[3], type_data(&[2]))
+            .install();
+
+        let ids: Vec<Vec<u8>> = type_chain(&[3], true).map(|v| v.unwrap().0).collect();
+        assert_eq!(ids, [[3], [2], [1]]);
+
+        let ids: Vec<Vec<u8>> = type_chain(&[1], true).map(|v| v.unwrap().0).collect();
+        assert_eq!(ids, [[1]]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "mock-host", feature = "nft-type-data"))]
+    fn type_chain_errors() {
+        use crate::host::mock::MockHost;
+
+        // 1 -> 2 -> 3 -> 1
+        MockHost::new()
+            .unit_data(&[1], type_data(&[3]))
+            .unit_data(&[2], type_data(&[1]))
+            .unit_data(&[3], type_data(&[2]))
+            .unit_data(&[4], type_data(&[5]))
+            .install();
+
+        let items: Vec<_> = type_chain(&[1], true).collect();
+        assert_eq!(items.len(), 4);
+        assert!(items[..3].iter().all(|v| v.is_ok()));
+        assert_eq!(items[3].as_ref().unwrap_err().code(), ERR_TYPE_CYCLE as u64);
+
+        // parent of type 4 doesn't exist
+        let items: Vec<_> = type_chain(&[4], true).collect();
+        assert_eq!(items.len(), 2);
+        assert_eq!(
+            items[1].as_ref().unwrap_err().code(),
+            ERR_UNIT_NOT_FOUND as u64
+        );
+
+        // chain of types longer than allowed
+        let mut host = MockHost::new().unit_data(&[0], type_data(&[]));
+        for id in 1..=MAX_TYPE_CHAIN_DEPTH as u8 {
+            host = host.unit_data(&[id], type_data(&[id - 1]));
+        }
+        host.install();
+        let items: Vec<_> = type_chain(&[MAX_TYPE_CHAIN_DEPTH as u8], true).collect();
+        assert_eq!(items.len(), MAX_TYPE_CHAIN_DEPTH + 1);
+        assert_eq!(
+            items[MAX_TYPE_CHAIN_DEPTH].as_ref().unwrap_err().code(),
+            ERR_TYPE_CHAIN_TOO_DEEP as u64
+        );
+        let items: Vec<_> = type_chain(&[MAX_TYPE_CHAIN_DEPTH as u8 - 1], true).collect();
+        assert!(items.iter().all(|v| v.is_ok()));
+    }
+
+    #[test]
+    #[cfg(all(
+        feature = "mock-host",
+        feature = "nft-type-data",
+        feature = "nft-token-data"
+    ))]
+    fn token_is_of_type_ok() {
+        use crate::host::mock::{Encoder, MockHost};
+
+        MockHost::new()
+            .unit_data(&[1], type_data(&[]))
+            .unit_data(&[2], type_data(&[1]))
+            .unit_data(&[9], type_data(&[]))
+            .unit_data(&[0xa], Encoder::new().bytes(1, &[2]).finish())
+            .install();
+
+        assert!(token_is_of_type(&[0xa], &[2]).unwrap());
+        assert!(token_is_of_type(&[0xa], &[1]).unwrap());
+        assert!(!token_is_of_type(&[0xa], &[9]).unwrap());
+        assert_eq!(
+            token_is_of_type(&[0xb], &[1]).unwrap_err().code(),
+            ERR_UNIT_NOT_FOUND as u64
+        );
+    }
+
+    #[test]
+    #[cfg(all(
+        feature = "mock-host",
+        feature = "nft-type-data",
+        feature = "nft-token-data"
+    ))]
+    fn load_missing_unit() {
+        use crate::host::mock::{Encoder, MockHost};
+
+        MockHost::new()
+            .unit_data(&[1], type_data(&[]))
+            .unit_data(&[0xa], Encoder::new().bytes(1, &[1]).finish())
+            .unit_data(&[0xb], Encoder::new().string(2, "no type").finish())
+            .install();
+
+        assert_eq!(TypeData::load(&[1], true).unwrap().symbol.unwrap(), "T");
+        assert_eq!(TokenData::load(&[0xa], true).unwrap().type_id.unwrap(), [1]);
+        assert_eq!(
+            TypeData::load(&[2], true).unwrap_err().code(),
+            ERR_UNIT_NOT_FOUND as u64
+        );
+        assert_eq!(
+            TokenData::load(&[2], true).unwrap_err().code(),
+            ERR_UNIT_NOT_FOUND as u64
+        );
+        // token without type
+        assert_eq!(
+            token_is_of_type(&[0xb], &[1]).unwrap_err().code(),
+            ERR_UNIT_NOT_FOUND as u64
+        );
+    }
+}
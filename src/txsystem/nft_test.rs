@@ -22,6 +22,8 @@ fn TokenData_from_1() {
             data: None,
             counter: Some(90),
             locked: Some(0),
+            owner_predicate: None,
+            data_update_predicate: None,
         }
     );
 
@@ -41,6 +43,8 @@ fn TokenData_from_1() {
             data: Some(vec![0x9, 0x1, 0x1]),
             counter: Some(90),
             locked: Some(1),
+            owner_predicate: None,
+            data_update_predicate: None,
         }
     );
 }
@@ -59,6 +63,11 @@ fn TypeData_from_1() {
             parent_id: None,
             symbol: Some("A".to_string()),
             name: Some("abcde".to_string()),
+            icon: None,
+            sub_type_creation_predicate: None,
+            token_minting_predicate: None,
+            token_type_owner_predicate: None,
+            data_update_predicate: None,
         }
     );
 
@@ -73,6 +82,11 @@ fn TypeData_from_1() {
             parent_id: Some(vec![0xff, 0x0, 0x7f, 0x80]),
             symbol: Some("oh!".to_string()),
             name: Some("qwerty".to_string()),
+            icon: None,
+            sub_type_creation_predicate: None,
+            token_minting_predicate: None,
+            token_type_owner_predicate: None,
+            data_update_predicate: None,
         }
     );
 }
@@ -5,6 +5,14 @@ Token transaction system APIs and data structures.
 /// Fungible- and Non-Fungible tokens share transaction system ID.
 pub const SYSTEM_ID: u32 = 2;
 
+#[cfg(any(feature = "ft-type-data", feature = "nft-type-data"))]
+use alloc::{string::String, vec::Vec};
+#[cfg(any(feature = "ft-type-data", feature = "nft-type-data"))]
+extern crate alloc;
+
+#[cfg(any(feature = "ft-type-data", feature = "nft-type-data"))]
+use crate::{AbDecode, decoder::Value, error::Error};
+
 /// Fungible Tokens API
 #[path = "ft.rs"]
 pub mod ft;
@@ -12,3 +20,27 @@ pub mod ft;
 /// Non-Fungible Token data structures and APIs.
 #[path = "nft.rs"]
 pub mod nft;
+
+/**
+Icon of the token type.
+*/
+#[cfg(any(feature = "ft-type-data", feature = "nft-type-data"))]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+#[derive(Default, AbDecode)]
+pub struct Icon {
+    /// MIME type of the image
+    #[ab(tag = 1)]
+    pub typ: Option<String>,
+    #[ab(tag = 2)]
+    pub data: Option<Vec<u8>>,
+}
+
+/// icon is encoded as tag-value encoded byte slice.
+#[cfg(any(feature = "ft-type-data", feature = "nft-type-data"))]
+impl TryFrom<Value> for Icon {
+    type Error = Error;
+
+    fn try_from(v: Value) -> Result<Self, Self::Error> {
+        Icon::from(Vec::<u8>::try_from(v)?)
+    }
+}
@@ -1,6 +1,6 @@
 #![no_std]
 
-use alphabill::{evaluation_ctx, predicate_result};
+use alphabill::{error::Error, evaluation_ctx, predicate};
 
 /**
 TODO: description of the predicate
@@ -9,8 +9,8 @@ TODO: description of the predicate
  - `0`: predicate evaluated to "true";
  - `1`: predicate evaluated to "false";
 */
-#[unsafe(no_mangle)]
-pub extern "C" fn {{predicate_name}}() -> u64 {
-    let txo = evaluation_ctx::tx_order();
-    predicate_result!(true)
+#[predicate]
+fn {{predicate_name}}() -> Result<bool, Error> {
+    let txo = evaluation_ctx::tx_order()?;
+    Ok(true)
 }
@@ -0,0 +1,204 @@
+use alphabill::{
+    PredicateResult,
+    api::{EvalResult, SignedByResult, VerifyResult},
+    error::Error,
+};
+
+#[test]
+fn eval_result_from() {
+    assert_eq!(EvalResult::from(0), EvalResult::True);
+    assert_eq!(EvalResult::from(1), EvalResult::False(0));
+    assert_eq!(EvalResult::from(0x0801), EvalResult::False(8));
+    assert_eq!(EvalResult::from(0x0100), EvalResult::Error(0x0100));
+    assert_eq!(EvalResult::from(0x020100), EvalResult::Error(0x020100));
+    // error code zero
+    assert_eq!(EvalResult::from(u64::MAX), EvalResult::Error(0));
+}
+
+#[test]
+fn signed_by_result() {
+    for code in 0..10 {
+        assert_eq!(SignedByResult::from(code).code(), code as u64);
+    }
+    assert_eq!(SignedByResult::from(7), SignedByResult::NilProof);
+    assert_eq!(SignedByResult::from(0x108), SignedByResult::Unknown(0x108));
+
+    assert_eq!(Error::from(SignedByResult::NilPKH).code(), 6);
+    assert_eq!(Error::from(SignedByResult::Unknown(0x108)).code(), 0xff);
+
+    assert_eq!(SignedByResult::True.into_u64(), 0);
+    assert_eq!(SignedByResult::False.into_u64(), 0x0101);
+    assert_eq!(SignedByResult::Unknown(0x108).into_u64(), 0x010801);
+}
+
+#[test]
+fn verify_result() {
+    for code in 0..10 {
+        assert_eq!(VerifyResult::from(code).code(), code as u64);
+    }
+    assert_eq!(VerifyResult::from(4), VerifyResult::InvalidMsgHash);
+    assert_eq!(VerifyResult::from(5), VerifyResult::Unknown(5));
+
+    assert_eq!(Error::from(VerifyResult::InvalidPubKey).code(), 2);
+    assert_eq!(VerifyResult::True.into_u64(), 0);
+    assert_eq!(VerifyResult::InvalidSignature.into_u64(), 0x0301);
+}
+
+#[cfg(feature = "mock-host")]
+#[test]
+fn signed_by_unknown_code() {
+    use alphabill::{
+        api,
+        evaluation_ctx::{HANDLE_ARGUMENT, HANDLE_TX_ORDER},
+        host::mock::MockHost,
+    };
+
+    MockHost::new()
+        .argument(&[0x41, 0xaa])
+        .tx_signed_by_pkh(&[1], &[0x41, 0xaa], SignedByResult::Unknown(42))
+        .install();
+    let pkh = alphabill::evaluation_ctx::add_var(vec![1]).pub_key_hash();
+    assert_eq!(
+        api::signed_by_pkh(HANDLE_TX_ORDER, pkh, HANDLE_ARGUMENT),
+        SignedByResult::Unknown(42)
+    );
+}
+
+#[cfg(feature = "mock-host")]
+#[test]
+fn satisfies() {
+    use alphabill::{
+        api,
+        evaluation_ctx::{self, HANDLE_ARGUMENT, HANDLE_TX_ORDER},
+        handle::Handle,
+        host::mock::MockHost,
+    };
+
+    const PREDICATE: [u8; 4] = [0x83, 0x00, 0x41, 0x01];
+
+    MockHost::new()
+        .argument(&[0x41, 0xaa])
+        .satisfies(&PREDICATE, &[0x41, 0xaa], 0)
+        .satisfies(&PREDICATE, &[0x41, 0xbb], 0x0701)
+        .install();
+    assert_eq!(
+        api::satisfies(HANDLE_TX_ORDER, &PREDICATE, HANDLE_ARGUMENT),
+        EvalResult::True
+    );
+    let txo = evaluation_ctx::tx_order().unwrap();
+    assert_eq!(txo.satisfies(&PREDICATE, HANDLE_ARGUMENT), EvalResult::True);
+    // no outcome registered for the predicate
+    assert_eq!(
+        api::satisfies(HANDLE_TX_ORDER, &[0x83, 0x00, 0x41, 0x00], HANDLE_ARGUMENT),
+        EvalResult::False(0)
+    );
+    // tx order handle is not valid
+    assert!(matches!(
+        api::satisfies(
+            Handle::from_raw(HANDLE_ARGUMENT.raw()),
+            &PREDICATE,
+            HANDLE_ARGUMENT
+        ),
+        EvalResult::Error(_)
+    ));
+
+    MockHost::new()
+        .argument(&[0x41, 0xbb])
+        .satisfies(&PREDICATE, &[0x41, 0xbb], 0x0701)
+        .install();
+    assert_eq!(
+        api::satisfies(HANDLE_TX_ORDER, &PREDICATE, HANDLE_ARGUMENT),
+        EvalResult::False(7)
+    );
+}
+
+#[cfg(feature = "mock-crypto")]
+#[test]
+fn verify_secp256k1() {
+    use alphabill::api::{self, VerifyResult};
+    use k256::ecdsa::{Signature, SigningKey, signature::hazmat::PrehashSigner};
+
+    let key = SigningKey::from_bytes(&[7u8; 32].into()).unwrap();
+    let pubkey = key
+        .verifying_key()
+        .to_encoded_point(true)
+        .as_bytes()
+        .to_vec();
+    let msg = b"price: 42";
+    let hash = api::digest_sha256(msg.to_vec());
+    let sig: Signature = key.sign_prehash(&hash).unwrap();
+    let mut sig = sig.to_bytes().to_vec();
+
+    assert!(matches!(
+        api::verify_secp256k1(&pubkey, &hash, &sig),
+        VerifyResult::True
+    ));
+    assert!(matches!(
+        api::verify_secp256k1_sha256(&pubkey, msg, &sig),
+        VerifyResult::True
+    ));
+    // with recovery ID
+    sig.push(1);
+    assert!(matches!(
+        api::verify_secp256k1(&pubkey, &hash, &sig),
+        VerifyResult::True
+    ));
+    assert!(matches!(
+        api::verify_secp256k1_sha256(&pubkey, b"price: 43", &sig),
+        VerifyResult::False
+    ));
+    assert!(matches!(
+        api::verify_secp256k1(&pubkey[1..], &hash, &sig),
+        VerifyResult::InvalidPubKey
+    ));
+    assert!(matches!(
+        api::verify_secp256k1(&pubkey, &hash, &sig[..10]),
+        VerifyResult::InvalidSignature
+    ));
+    // trailing garbage after the recovery ID, recovery ID out of range
+    let mut long_sig = sig.clone();
+    long_sig.push(0);
+    assert!(matches!(
+        api::verify_secp256k1(&pubkey, &hash, &long_sig),
+        VerifyResult::InvalidSignature
+    ));
+    sig[64] = 4;
+    assert!(matches!(
+        api::verify_secp256k1(&pubkey, &hash, &sig),
+        VerifyResult::InvalidSignature
+    ));
+    sig[64] = 1;
+    assert!(matches!(
+        api::verify_secp256k1(&pubkey, &hash[1..], &sig),
+        VerifyResult::InvalidMsgHash
+    ));
+}
+
+#[cfg(feature = "mock-crypto")]
+#[test]
+fn verify_ed25519() {
+    use alphabill::api::{self, VerifyResult};
+    use ed25519_dalek::{Signer, SigningKey};
+
+    let key = SigningKey::from_bytes(&[9u8; 32]);
+    let pubkey = key.verifying_key().to_bytes();
+    let msg = b"price: 42";
+    let sig = key.sign(msg).to_bytes();
+
+    assert!(matches!(
+        api::verify_ed25519(&pubkey, msg, &sig),
+        VerifyResult::True
+    ));
+    assert!(matches!(
+        api::verify_ed25519(&pubkey, b"price: 43", &sig),
+        VerifyResult::False
+    ));
+    assert!(matches!(
+        api::verify_ed25519(&pubkey[1..], msg, &sig),
+        VerifyResult::InvalidPubKey
+    ));
+    assert!(matches!(
+        api::verify_ed25519(&pubkey, msg, &sig[1..]),
+        VerifyResult::InvalidSignature
+    ));
+}
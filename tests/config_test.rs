@@ -0,0 +1,188 @@
+use alphabill::{
+    PredicateConfig,
+    cbor::{self, ERR_INVALID_TYPE, ERR_MISSING_ITEM, FromItem, Item},
+};
+
+#[derive(PredicateConfig, Debug, PartialEq)]
+struct Range {
+    from: u64,
+    #[config(default)]
+    to: u32,
+}
+
+#[derive(PredicateConfig, Debug, PartialEq)]
+struct Config {
+    #[config(error = 0x0c)]
+    threshold: u8,
+    range: Range,
+    name: Option<String>,
+    #[config(rest)]
+    keys: Vec<Vec<u8>>,
+}
+
+fn decode<T: FromItem>(data: &[u8]) -> Result<T, alphabill::error::Error> {
+    T::from_item(&cbor::decode(data).unwrap())
+}
+
+#[test]
+fn all_fields() {
+    // [2, [1, 9], "foo", h'01', h'0202']
+    let data = [
+        0x85, 0x02, 0x82, 0x01, 0x09, 0x63, 0x66, 0x6f, 0x6f, 0x41, 0x01, 0x42, 0x02, 0x02,
+    ];
+    assert_eq!(
+        decode::<Config>(&data).unwrap(),
+        Config {
+            threshold: 2,
+            range: Range { from: 1, to: 9 },
+            name: Some("foo".into()),
+            keys: vec![vec![1], vec![2, 2]],
+        }
+    );
+}
+
+#[test]
+fn optional_items() {
+    // [2, [1]]
+    let data = [0x82, 0x02, 0x81, 0x01];
+    assert_eq!(
+        decode::<Config>(&data).unwrap(),
+        Config {
+            threshold: 2,
+            range: Range { from: 1, to: 0 },
+            name: None,
+            keys: vec![],
+        }
+    );
+    // [2, [1], null]
+    let data = [0x83, 0x02, 0x81, 0x01, 0xf6];
+    assert_eq!(decode::<Config>(&data).unwrap().name, None);
+}
+
+#[test]
+fn errors() {
+    // not an array
+    assert_eq!(
+        decode::<Config>(&[0x02]).unwrap_err().code(),
+        ERR_INVALID_TYPE as u64
+    );
+    // threshold doesn't fit into u8: [256, [1]]
+    let data = [0x82, 0x19, 0x01, 0x00, 0x81, 0x01];
+    assert_eq!(decode::<Config>(&data).unwrap_err().code(), 0x070c);
+    // range.from is missing: [2, []]
+    let data = [0x82, 0x02, 0x80];
+    assert_eq!(
+        decode::<Config>(&data).unwrap_err().code(),
+        (ERR_MISSING_ITEM as u64) << 16 | 0x0102
+    );
+    // range is missing: [2]
+    let data = [0x81, 0x02];
+    assert_eq!(decode::<Config>(&data).unwrap_err().code(), 0x0802);
+    // second key is not bytes: [2, [1], null, h'01', 5]
+    let data = [0x85, 0x02, 0x81, 0x01, 0xf6, 0x41, 0x01, 0x05];
+    assert_eq!(decode::<Config>(&data).unwrap_err().code(), 0x0704);
+}
+
+#[test]
+fn item_field() {
+    #[derive(PredicateConfig)]
+    struct Raw {
+        v: Item,
+    }
+    let cfg = decode::<Raw>(&[0x81, 0xf5]).unwrap();
+    assert_eq!(cfg.v, Item::Bool(true));
+}
+
+#[test]
+fn cfg_disabled_fields() {
+    #[derive(PredicateConfig, Debug)]
+    #[config(len = 3)]
+    struct Terms {
+        #[cfg(false)]
+        #[config(index = 0)]
+        first: u64,
+        #[cfg(true)]
+        #[config(index = 1)]
+        second: u64,
+        #[config(index = 2)]
+        third: u32,
+    }
+
+    // [1, 2, 3]
+    let terms: Terms = decode(&[0x83, 0x01, 0x02, 0x03]).unwrap();
+    assert_eq!((terms.second, terms.third), (2, 3));
+
+    // [1, 2, "x"] - error code is the position of the item
+    let err = decode::<Terms>(&[0x83, 0x01, 0x02, 0x61, 0x78]).unwrap_err();
+    assert_eq!(err.code(), ((ERR_INVALID_TYPE as u64) << 8) | 3);
+
+    // [2, 3] and [1, 2, 3, 4]
+    let err = decode::<Terms>(&[0x82, 0x02, 0x03]).unwrap_err();
+    assert_eq!(err.code(), ERR_INVALID_TYPE as u64);
+    let err = decode::<Terms>(&[0x84, 0x01, 0x02, 0x03, 0x04]).unwrap_err();
+    assert_eq!(err.code(), ERR_INVALID_TYPE as u64);
+}
+
+#[cfg(feature = "mock-host")]
+#[test]
+fn var_data() {
+    use alphabill::{
+        evaluation_ctx::{self, HANDLE_CONFIG},
+        host::mock::MockHost,
+    };
+
+    MockHost::new().config(&[0x82, 1, 2]).install();
+    assert_eq!(
+        evaluation_ctx::var_data(HANDLE_CONFIG).unwrap(),
+        [0x82, 1, 2]
+    );
+    // raw variables are only serialized with version 0
+    assert!(evaluation_ctx::serialize_obj(HANDLE_CONFIG, 1).is_empty());
+}
+
+#[cfg(feature = "mock-host")]
+#[test]
+fn handles() {
+    use alphabill::{
+        api::{SignedByResult, signed_by_pkh},
+        evaluation_ctx::{self, HANDLE_TX_ORDER},
+        handle::{Handle, PubKeyHash},
+        host::mock::MockHost,
+    };
+
+    #[derive(PredicateConfig)]
+    struct Owners {
+        #[config(handle)]
+        owner: Handle<PubKeyHash>,
+        #[config(handle)]
+        backup: Option<Handle<PubKeyHash>>,
+        #[config(handle, rest)]
+        others: Vec<Handle<PubKeyHash>>,
+    }
+
+    // [h'0102', null, h'03', h'04']
+    MockHost::new()
+        .config(&[0x84, 0x42, 1, 2, 0xf6, 0x41, 3, 0x41, 4])
+        .argument(&[0x41, 0xaa])
+        .tx_signed_by_pkh(&[1, 2], &[0xaa], SignedByResult::True)
+        .install();
+    let cfg: Owners = evaluation_ctx::config().unwrap();
+    assert!(cfg.backup.is_none());
+    assert_eq!(cfg.others.len(), 2);
+
+    // argument isn't an array
+    assert_eq!(
+        evaluation_ctx::argument::<Owners>().err().unwrap().code(),
+        ERR_INVALID_TYPE as u64
+    );
+
+    let proof = cbor::add_var(&Item::Bytes(vec![0xaa])).proof();
+    assert!(matches!(
+        signed_by_pkh(HANDLE_TX_ORDER, cfg.owner, proof),
+        SignedByResult::True
+    ));
+    assert!(matches!(
+        signed_by_pkh(HANDLE_TX_ORDER, cfg.others[0], proof),
+        SignedByResult::False
+    ));
+}
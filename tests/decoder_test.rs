@@ -77,7 +77,7 @@ fn iterator() {
         0x8, 0x5, 0x2, 0x0, 0x0, 0x0, 0x3, 0x21, 0x43, 0x65, 0x87, 0x4, 0x3, 0x0, 0x0, 0x0, 0x73,
         0x74, 0x72,
     ];
-    let dec = TagValueIter::new(&data);
+    let dec = TagValueIter::new(data);
     let items: Vec<(u8, Value)> = dec.collect();
     assert_eq!(
         items,
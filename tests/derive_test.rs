@@ -0,0 +1,103 @@
+use alphabill::{
+    AbDecode,
+    decoder::{ERR_TRUNCATED, Value},
+};
+
+#[derive(AbDecode, Default, Debug, PartialEq)]
+struct Config {
+    #[ab(tag = 2)]
+    threshold: u64,
+    #[ab(tag = 1)]
+    name: Option<String>,
+    #[ab(tag = 3)]
+    pkh: Option<Vec<u8>>,
+    #[ab(tag = 4)]
+    counter: Option<u32>,
+    #[cfg(any())]
+    #[ab(tag = 5)]
+    disabled: u64,
+    not_decoded: u64,
+}
+
+#[test]
+fn all_fields() {
+    let data = [
+        0x1, 0x4, 0x2, 0x0, 0x0, 0x0, 0x41, 0x42, 0x2, 0x2, 0x7, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x3, 0x1, 0x2, 0x0, 0x0, 0x0, 0xa, 0xb, 0x4, 0x3, 0x5, 0x0, 0x0, 0x0,
+    ];
+    assert_eq!(
+        Config::from(data).unwrap(),
+        Config {
+            threshold: 7,
+            name: Some("AB".to_string()),
+            pkh: Some(vec![0xa, 0xb]),
+            counter: Some(5),
+            not_decoded: 0,
+        }
+    );
+}
+
+#[test]
+fn missing_and_unknown_fields() {
+    // tag 2 = U32(3), tag 9 = String("x"), tag 5 = U64(1) (field is disabled)
+    let data = [
+        0x2, 0x3, 0x3, 0x0, 0x0, 0x0, 0x9, 0x4, 0x1, 0x0, 0x0, 0x0, 0x78, 0x5, 0x2, 0x1, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0,
+    ];
+    assert_eq!(
+        Config::from(&data[..]).unwrap(),
+        Config {
+            threshold: 3,
+            ..Default::default()
+        }
+    );
+    assert_eq!(Config::from(Vec::new()).unwrap(), Config::default());
+}
+
+#[test]
+fn invalid_field() {
+    // tag 4 = U64(1), only u32 is accepted for the field
+    let data = [0x4, 0x2, 0x1, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0];
+    assert_eq!(Config::from(data).unwrap_err().code(), 0x0104);
+
+    // tag 2 = String("x")
+    let data = [0x2, 0x4, 0x1, 0x0, 0x0, 0x0, 0x78];
+    assert_eq!(Config::from(data).unwrap_err().code(), 0x0102);
+}
+
+#[derive(AbDecode, Default, Debug)]
+struct Counters {
+    #[ab(tag = 1)]
+    n64: Option<u64>,
+    #[ab(tag = 2)]
+    n32: Option<u32>,
+}
+
+#[test]
+fn invalid_option() {
+    // error of the inner type is chained with the tag, regardless of the type
+    let data = [0x1, 0x4, 0x1, 0x0, 0x0, 0x0, 0x78];
+    assert_eq!(Counters::from(data).unwrap_err().code(), 0x0101);
+    let data = [0x2, 0x4, 0x1, 0x0, 0x0, 0x0, 0x78];
+    assert_eq!(Counters::from(data).unwrap_err().code(), 0x0102);
+    let data = [0x1, 0x2, 0x9, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0];
+    assert_eq!(Counters::from(data).unwrap().n64, Some(9));
+}
+
+#[test]
+fn truncated() {
+    let data = [0x2, 0x2, 0x1, 0x0, 0x0];
+    assert_eq!(Config::from(data).unwrap_err().code(), ERR_TRUNCATED as u64);
+}
+
+#[derive(AbDecode, Default)]
+struct Generic<T: Default + TryFrom<Value, Error = alphabill::error::Error>> {
+    #[ab(tag = 1)]
+    v: T,
+}
+
+#[test]
+fn generic() {
+    let r = Generic::<u64>::from([0x1, 0x3, 0x9, 0x0, 0x0, 0x0]).unwrap();
+    assert_eq!(r.v, 9);
+}
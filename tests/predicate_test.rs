@@ -0,0 +1,65 @@
+use std::cell::Cell;
+
+use alphabill::{error::Error, predicate};
+
+#[predicate]
+pub fn always_true() -> bool {
+    true
+}
+
+/// returns the error of loading the value as is
+#[predicate]
+fn unchained() -> Result<bool, Error> {
+    Ok(value()? > 5)
+}
+
+/// checks the value, docs must be preserved
+#[predicate(error(0x0c, "invalid value"), error(0x0d, "value not loaded"))]
+fn check_value() -> Result<bool, Error> {
+    let v = value().map_err(|err| err.chain(0x0d))?;
+    if v > 10 {
+        return Err(Error::new(0x0c));
+    }
+    Ok(v > 5)
+}
+
+thread_local! {
+    static VALUE: Cell<Result<u64, u8>> = const { Cell::new(Ok(0)) };
+}
+
+fn value() -> Result<u64, Error> {
+    VALUE.get().map_err(Error::new)
+}
+
+fn set_value(v: Result<u64, u8>) {
+    VALUE.set(v);
+}
+
+#[test]
+fn predicate_export() {
+    assert_eq!(always_true(), 0);
+
+    set_value(Ok(6));
+    assert_eq!(check_value(), 0);
+    set_value(Ok(5));
+    assert_eq!(check_value(), 1);
+    set_value(Ok(11));
+    assert_eq!(check_value(), 0x0c);
+    set_value(Err(2));
+    assert_eq!(check_value(), 0x020d);
+    // the code of the error is chained with 0x0d so low byte isn't 1
+    set_value(Err(1));
+    assert_eq!(check_value(), 0x010d);
+}
+
+#[test]
+fn predicate_error_shifted() {
+    // error code with low byte 1 is shifted
+    set_value(Err(1));
+    assert_eq!(unchained(), 0x0100);
+    set_value(Err(2));
+    assert_eq!(unchained(), 0x02);
+    // error code zero
+    set_value(Err(0));
+    assert_eq!(unchained(), u64::MAX);
+}